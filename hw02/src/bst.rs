@@ -18,6 +18,18 @@ impl<T: Ord> BST<T> {
     }
 }
 
+impl<T: Ord> Drop for BST<T> {
+    /// Free the nodes one at a time, since a tree built from sorted values
+    /// is too deep to drop recursively.
+    fn drop(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(mut box_node) = stack.pop() {
+            stack.extend(box_node.left.take());
+            stack.extend(box_node.right.take());
+        }
+    }
+}
+
 impl<T: Ord> BST<T> {
     pub fn insert(&mut self, val: T) -> bool {
         let new_node = Box::new(Node {
@@ -30,7 +42,7 @@ impl<T: Ord> BST<T> {
                 return true;
         }
         let mut cur_link = self.root.as_mut();
-        while let Some(mut box_node) = cur_link {
+        while let Some(box_node) = cur_link {
             if new_node.val == box_node.val {
                 return false;
             }
@@ -0,0 +1,50 @@
+//! Inserting values in sorted order, the worst case for `BST` here and in
+//! hw02, which grow into one long right spine, next to `AVL`, which stays
+//! balanced.
+
+#[macro_use]
+extern crate criterion;
+extern crate hw02;
+extern crate hw03;
+
+use criterion::{BenchmarkId, Criterion};
+
+use hw03::avl::AVL;
+use hw03::bst::BST;
+
+fn sorted_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sorted_insert");
+    for &n in &[100, 1000, 5000] {
+        group.bench_with_input(BenchmarkId::new("BST", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut bst = BST::new();
+                for val in 0..n {
+                    bst.insert(val);
+                }
+                bst
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("hw02 BST", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut bst = hw02::bst::BST::new();
+                for val in 0..n {
+                    bst.insert(val);
+                }
+                bst
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("AVL", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut avl = AVL::new();
+                for val in 0..n {
+                    avl.insert(val);
+                }
+                avl
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sorted_insert);
+criterion_main!(benches);
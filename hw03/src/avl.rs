@@ -0,0 +1,367 @@
+//! An AVL tree: a binary search tree that rotates nodes as values go in so
+//! that, at every node, the heights of the two subtrees differ by at most
+//! one. Unlike `BST`, it stays O(log n) deep however its values arrive.
+
+use std::cmp::{self, Ordering};
+
+use bst::{Subtree, Walk};
+
+type Link<T> = Option<Box<Node<T>>>;
+
+#[derive(Debug)]
+struct Node<T: Ord> {
+    val: T,
+    /// The number of nodes on the longest path down from this one, itself
+    /// included.
+    height: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+#[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct AVL<T: Ord> {
+    root: Link<T>,
+    len: usize,
+}
+
+fn height<T: Ord>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |box_node| box_node.height)
+}
+
+impl<T: Ord> Node<T> {
+    fn update_height(&mut self) {
+	self.height = 1 + cmp::max(height(&self.left), height(&self.right));
+    }
+
+    /// How much taller the left subtree is than the right.
+    fn balance(&self) -> isize {
+	height(&self.left) as isize - height(&self.right) as isize
+    }
+}
+
+impl<T: Ord> AVL<T> {
+    pub fn new() -> Self {
+        AVL { root: None, len: 0 }
+    }
+
+    /// The number of values in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of nodes on the longest path from the root to a leaf.
+    pub fn height(&self) -> usize {
+	height(&self.root)
+    }
+}
+
+impl<T: Ord> Default for AVL<T> {
+    fn default() -> Self {
+        AVL::new()
+    }
+}
+
+impl<T: Ord> AVL<T> {
+    pub fn insert(&mut self, val: T) -> bool {
+	let inserted = insert_at(&mut self.root, val);
+	if inserted {
+	    self.len += 1;
+	}
+	inserted
+    }
+}
+
+/// Insert `val` into the subtree at `link` and rebalance each node on the
+/// way back up. The recursion only goes as deep as the tree, which the
+/// balancing keeps logarithmic.
+fn insert_at<T: Ord>(link: &mut Link<T>, val: T) -> bool {
+    let inserted = match *link {
+	None => {
+	    *link = Some(Box::new(Node { val, height: 1, left: None, right: None }));
+	    return true;
+	},
+	Some(ref mut box_node) => match val.cmp(&box_node.val) {
+	    Ordering::Less => insert_at(&mut box_node.left, val),
+	    Ordering::Greater => insert_at(&mut box_node.right, val),
+	    Ordering::Equal => false,
+	},
+    };
+    if inserted {
+	let box_node = link.take().unwrap();
+	*link = Some(rebalance(box_node));
+    }
+    inserted
+}
+
+/// Restore the balance of a node whose subtrees' heights differ by at most
+/// two, returning the node that takes its place.
+fn rebalance<T: Ord>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    node.update_height();
+    let balance = node.balance();
+    if balance > 1 {
+	if node.left.as_ref().unwrap().balance() < 0 {
+	    node.left = node.left.take().map(rotate_left);
+	}
+	rotate_right(node)
+    } else if balance < -1 {
+	if node.right.as_ref().unwrap().balance() > 0 {
+	    node.right = node.right.take().map(rotate_right);
+	}
+	rotate_left(node)
+    } else {
+	node
+    }
+}
+
+/// Lift `node`'s left child into its place, making `node` its right child.
+fn rotate_right<T: Ord>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut left = node.left.take().unwrap();
+    node.left = left.right.take();
+    node.update_height();
+    left.right = Some(node);
+    left.update_height();
+    left
+}
+
+/// Lift `node`'s right child into its place, making `node` its left child.
+fn rotate_left<T: Ord>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut right = node.right.take().unwrap();
+    node.right = right.left.take();
+    node.update_height();
+    right.left = Some(node);
+    right.update_height();
+    right
+}
+
+impl<T: Ord> AVL<T> {
+    pub fn search(&self, val: T) -> bool {
+	let mut cur_link = self.root.as_ref();
+	while let Some(box_node) = cur_link {
+	    match val.cmp(&box_node.val) {
+		Ordering::Less => cur_link = box_node.left.as_ref(),
+		Ordering::Greater => cur_link = box_node.right.as_ref(),
+		Ordering::Equal => return true,
+	    }
+	}
+	false
+    }
+}
+
+// Iterators, which walk the tree the same way as `BST`'s.
+
+impl<'a, T: Ord> Subtree for &'a Node<T> {
+    type Val = &'a T;
+    fn split(self) -> (Option<Self>, &'a T, Option<Self>) {
+	(self.left.as_deref(), &self.val, self.right.as_deref())
+    }
+}
+
+impl<'a, T: Ord> Subtree for &'a mut Node<T> {
+    type Val = &'a mut T;
+    fn split(self) -> (Option<Self>, &'a mut T, Option<Self>) {
+	let Node { ref mut val, ref mut left, ref mut right, .. } = *self;
+	(left.as_deref_mut(), val, right.as_deref_mut())
+    }
+}
+
+impl<T: Ord> Subtree for Box<Node<T>> {
+    type Val = T;
+    fn split(self) -> (Option<Self>, T, Option<Self>) {
+	let node = *self;
+	(node.left, node.val, node.right)
+    }
+}
+
+// IntoIter
+pub struct IntoIter<T: Ord>(Walk<Box<Node<T>>>);
+
+impl<T: Ord> IntoIterator for AVL<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+	IntoIter(Walk::new(self.root, self.len))
+    }
+}
+
+impl<T: Ord> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+	self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<T: Ord> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back()
+    }
+}
+
+impl<T: Ord> ExactSizeIterator for IntoIter<T> {}
+
+//Iter
+pub struct Iter<'a, T: 'a + Ord>(Walk<&'a Node<T>>);
+
+impl<T: Ord> AVL<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+	Iter(Walk::new(self.root.as_deref(), self.len))
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a AVL<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+	self.iter()
+    }
+}
+
+impl<'a, T: Ord> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+	self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back()
+    }
+}
+
+impl<'a, T: Ord> ExactSizeIterator for Iter<'a, T> {}
+
+//IterMut
+pub struct IterMut<'a, T: 'a + Ord>(Walk<&'a mut Node<T>>);
+
+impl<T: Ord> AVL<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+	IterMut(Walk::new(self.root.as_deref_mut(), self.len))
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a mut AVL<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+	self.iter_mut()
+    }
+}
+
+impl<'a, T: Ord> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+	self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back()
+    }
+}
+
+impl<'a, T: Ord> ExactSizeIterator for IterMut<'a, T> {}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
+
+    use super::{height, Link, AVL};
+
+    /// Check every AVL invariant below `link` — values in order and within
+    /// `(lo, hi)`, heights stored correctly, subtrees within one of each
+    /// other's height — and return the subtree's height.
+    fn check<T: Ord + Copy>(link: &Link<T>, lo: Option<T>, hi: Option<T>) -> usize {
+	let box_node = match *link {
+	    Some(ref box_node) => box_node,
+	    None => return 0,
+	};
+	assert!(lo.is_none_or(|lo| lo < box_node.val), "out of order");
+	assert!(hi.is_none_or(|hi| box_node.val < hi), "out of order");
+	let left = check(&box_node.left, lo, Some(box_node.val));
+	let right = check(&box_node.right, Some(box_node.val), hi);
+	assert!((left as isize - right as isize).abs() <= 1, "unbalanced");
+	assert_eq!(box_node.height, 1 + left.max(right));
+	box_node.height
+    }
+
+    /// The tallest an AVL tree of `len` nodes can be, 1.44 log2(len + 2).
+    fn max_height(len: usize) -> usize {
+	(1.4405 * ((len + 2) as f64).log2()) as usize
+    }
+
+    #[test]
+    fn basics() {
+	let mut avl = AVL::new();
+	assert!(!avl.search(2));
+	assert!(avl.insert(2));
+	assert!(avl.search(2));
+	assert!(!avl.insert(2));
+	assert!(avl.insert(1));
+	assert!(avl.insert(3));
+	assert_eq!(avl.len(), 3);
+	assert_eq!(avl.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+	for val in avl.iter_mut() {
+	    *val *= 10;
+	}
+	assert_eq!(avl.into_iter().rev().collect::<Vec<_>>(), vec![30, 20, 10]);
+    }
+
+    #[test]
+    fn sorted_inserts_stay_balanced() {
+	let mut avl = AVL::new();
+	for val in 0..100_000 {
+	    avl.insert(val);
+	}
+	check(&avl.root, None, None);
+	assert_eq!(avl.height(), 17);
+	assert!(avl.search(0) && avl.search(99_999) && !avl.search(100_000));
+	assert!(avl.iter().cloned().eq(0..100_000));
+
+	let mut avl = AVL::new();
+	for val in (0..1000).rev() {
+	    avl.insert(val);
+	}
+	assert_eq!(height(&avl.root), check(&avl.root, None, None));
+	assert_eq!(avl.height(), 10);
+    }
+
+    proptest! {
+	#[test]
+	fn random_inserts_stay_balanced(vals in prop::collection::vec(any::<u16>(), 0..500)) {
+	    let mut avl = AVL::new();
+	    let mut set = BTreeSet::new();
+	    for &val in &vals {
+		prop_assert_eq!(avl.insert(val), set.insert(val));
+		check(&avl.root, None, None);
+	    }
+	    prop_assert!(avl.height() <= max_height(avl.len()));
+	    prop_assert!(avl.iter().eq(set.iter()));
+	    for &val in &vals {
+		prop_assert!(avl.search(val));
+	    }
+	}
+    }
+}
@@ -1,151 +1,713 @@
-type Link<T> = Option<Box<Node<T>>>;
+use std::borrow::Borrow;
+use std::collections::VecDeque;
+use std::ops::{Bound, RangeBounds};
 
+pub(crate) type Link<K, V> = Option<Box<Node<K, V>>>;
+
+/// A node of the tree under both `BST`, whose nodes hold a value as the key
+/// and `()`, and `BstMap`, whose nodes hold a key and its value.
 #[derive(Debug)]
-struct Node<T: Ord> {
-    val: T,
-    left: Link<T>,
-    right: Link<T>,
+pub(crate) struct Node<K: Ord, V> {
+    pub(crate) key: K,
+    pub(crate) val: V,
+    /// The number of keys in the subtree rooted here, this one included.
+    size: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+/// The number of keys in the subtree at `link`.
+pub(crate) fn size<K: Ord, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |box_node| box_node.size)
 }
 
 #[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
 pub struct BST<T: Ord> {
-    root: Link<T>,
+    root: Link<T, ()>,
 }
 
 impl<T: Ord> BST<T> {
     pub fn new() -> Self {
         BST { root: None }
     }
+
+    /// The number of values in the tree.
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+}
+
+impl<T: Ord> Default for BST<T> {
+    fn default() -> Self {
+        BST::new()
+    }
+}
+
+impl<T: Ord> Drop for BST<T> {
+    fn drop(&mut self) {
+	free(&mut self.root);
+    }
+}
+
+/// Free the nodes of the subtree at `link` one at a time. Dropping the boxes
+/// recursively would take a stack frame per level, and sorted inserts make
+/// the tree as deep as it is long.
+pub(crate) fn free<K: Ord, V>(link: &mut Link<K, V>) {
+    let mut stack: Vec<_> = link.take().into_iter().collect();
+    while let Some(mut box_node) = stack.pop() {
+	stack.extend(box_node.left.take());
+	stack.extend(box_node.right.take());
+    }
 }
 
 impl<T: Ord> BST<T> {
     pub fn insert(&mut self, val: T) -> bool {
-        let new_node = Box::new(Node {
-            val: val,
-            left: None,
-            right: None,
-        });
-        if let None = self.root.as_mut() {
-                self.root = Some(new_node);
-                return true;
+        // Every node on the way down gains a value, so make sure it is new
+        // before counting it.
+        if self.contains(&val) {
+            return false;
         }
-        let mut cur_link = self.root.as_mut();
-        while let Some(mut box_node) = cur_link {
-            if new_node.val == box_node.val {
-                return false;
-            }
-            if new_node.val < box_node.val {
-                match box_node.left.as_mut() {
-                    None => {
-                        box_node.left = Some(new_node);
-                        return true;
-                    },
-                    Some(_) => {
-                        cur_link = box_node.left.as_mut();
-                    }
-                }
-            } else {
-                match box_node.right.as_mut() {
-                    None => {
-                        box_node.right = Some(new_node);
-                        return true;
-                    },
-                    Some(_) => {
-                        cur_link = box_node.right.as_mut();
-                    }
-                }
-            }
-        }
-        false
+        insert_new(&mut self.root, val, ());
+        true
     }
 }
 
+/// Add `key` with `val` to the subtree at `link`, counting it in every node
+/// on the way down, and return its node. `key` must not be there already.
+pub(crate) fn insert_new<K: Ord, V>(link: &mut Link<K, V>, key: K, val: V) -> &mut Node<K, V> {
+    let mut cur_link = link;
+    while cur_link.is_some() {
+	let box_node = cur_link.as_mut().unwrap();
+	box_node.size += 1;
+	cur_link = if key < box_node.key { &mut box_node.left } else { &mut box_node.right };
+    }
+    cur_link.get_or_insert(Box::new(Node { key, val, size: 1, left: None, right: None }))
+}
+
 impl<T: Ord> BST<T> {
     pub fn search(&self, val: T) -> bool {
-	let mut cur_link = self.root.as_ref();
-	while let Some(box_node) = cur_link {
-            if val == box_node.val  {
-                return true;
-            }
-            if val < box_node.val {
-                cur_link = box_node.left.as_ref();
-            } else {
-                cur_link = box_node.right.as_ref();
-            }
+	self.contains(&val)
+    }
+
+    fn contains(&self, val: &T) -> bool {
+	find(&self.root, val).is_some()
+    }
+}
+
+/// The node in the subtree at `link` that holds `key`.
+pub(crate) fn find<'a, K, V, Q>(link: &'a Link<K, V>, key: &Q) -> Option<&'a Node<K, V>>
+    where K: Ord + Borrow<Q>, Q: ?Sized + Ord {
+    let mut cur_link = link.as_deref();
+    while let Some(node) = cur_link {
+	if key == node.key.borrow() {
+	    return Some(node);
+	}
+	if key < node.key.borrow() {
+	    cur_link = node.left.as_deref();
+	} else {
+	    cur_link = node.right.as_deref();
 	}
-        false
+    }
+    None
+}
+
+/// The node in the subtree at `link` that holds `key`, to change its value.
+pub(crate) fn find_mut<'a, K, V, Q>(link: &'a mut Link<K, V>, key: &Q) -> Option<&'a mut Node<K, V>>
+    where K: Ord + Borrow<Q>, Q: ?Sized + Ord {
+    let mut cur_link = link.as_deref_mut();
+    while let Some(node) = cur_link {
+	if key == node.key.borrow() {
+	    return Some(node);
+	}
+	if key < node.key.borrow() {
+	    cur_link = node.left.as_deref_mut();
+	} else {
+	    cur_link = node.right.as_deref_mut();
+	}
+    }
+    None
+}
+
+/// An empty link where a key would go, with the sizes of the nodes above it,
+/// which count the key once it is filled in.
+pub(crate) struct Vacancy<'a, K: 'a + Ord, V: 'a> {
+    link: &'a mut Link<K, V>,
+    sizes: Vec<&'a mut usize>,
+}
+
+impl<'a, K: Ord, V> Vacancy<'a, K, V> {
+    /// Put a new node for `key` with `val` in the link.
+    pub(crate) fn fill(self, key: K, val: V) -> &'a mut Node<K, V> {
+	for size in self.sizes {
+	    *size += 1;
+	}
+	self.link.get_or_insert(Box::new(Node { key, val, size: 1, left: None, right: None }))
+    }
+}
+
+/// The node in the subtree at `link` that holds `key`, or else the place it
+/// would go, found in one walk down.
+pub(crate) fn locate<'a, K: Ord, V>(link: &'a mut Link<K, V>, key: &K) -> Result<&'a mut Node<K, V>, Vacancy<'a, K, V>> {
+    let mut cur_link = link;
+    let mut sizes = Vec::new();
+    loop {
+	match *cur_link {
+	    None => return Err(Vacancy { link: cur_link, sizes }),
+	    Some(ref mut box_node) => {
+		if *key == box_node.key {
+		    return Ok(box_node);
+		}
+		let Node { key: ref node_key, ref mut size, ref mut left, ref mut right, .. } = **box_node;
+		sizes.push(size);
+		cur_link = if key < node_key { left } else { right };
+	    },
+	}
+    }
+}
+
+// Removal
+
+impl<T: Ord> BST<T> {
+    /// Remove `val` from the tree, returning it if it was there.
+    pub fn remove(&mut self, val: &T) -> Option<T> {
+	remove(&mut self.root, val).map(|node| node.key)
+    }
+
+    /// Remove and return the smallest value in the tree.
+    pub fn pop_min(&mut self) -> Option<T> {
+	pop_min_node(&mut self.root).map(|node| node.key)
+    }
+
+    /// Remove and return the largest value in the tree.
+    pub fn pop_max(&mut self) -> Option<T> {
+	pop_max_node(&mut self.root).map(|node| node.key)
+    }
+}
+
+/// Unlink the node holding `key` from the subtree at `link` and return it,
+/// keeping the sizes above it up to date. A node with two children is
+/// replaced by its in-order successor, the leftmost node in its right
+/// subtree.
+pub(crate) fn remove<K, V, Q>(link: &mut Link<K, V>, key: &Q) -> Option<Box<Node<K, V>>>
+    where K: Ord + Borrow<Q>, Q: ?Sized + Ord {
+    find(link, key)?;
+    // Find the link that holds the node to remove, taking it out of the
+    // count of every node above it.
+    let mut cur_link = link;
+    while cur_link.as_ref().is_some_and(|box_node| box_node.key.borrow() != key) {
+	let box_node = cur_link.as_mut().unwrap();
+	box_node.size -= 1;
+	cur_link = if key < box_node.key.borrow() { &mut box_node.left } else { &mut box_node.right };
+    }
+    let mut node = cur_link.take()?;
+    *cur_link = match (node.left.take(), node.right.take()) {
+	(None, None) => None,
+	(Some(left), None) => Some(left),
+	(None, Some(right)) => Some(right),
+	(Some(left), Some(right)) => {
+	    let mut right = Some(right);
+	    let mut successor = pop_min_node(&mut right).unwrap();
+	    successor.size = node.size - 1;
+	    successor.left = Some(left);
+	    successor.right = right;
+	    Some(successor)
+	},
+    };
+    Some(node)
+}
+
+/// Unlink the leftmost node of the subtree at `link`, putting its right
+/// subtree in its place and keeping the sizes above it up to date.
+fn pop_min_node<K: Ord, V>(link: &mut Link<K, V>) -> Option<Box<Node<K, V>>> {
+    let mut cur_link = link;
+    while cur_link.as_ref()?.left.is_some() {
+	let box_node = cur_link.as_mut().unwrap();
+	box_node.size -= 1;
+	cur_link = &mut box_node.left;
+    }
+    let mut node = cur_link.take()?;
+    *cur_link = node.right.take();
+    Some(node)
+}
+
+/// Unlink the rightmost node of the subtree at `link`, putting its left
+/// subtree in its place and keeping the sizes above it up to date.
+fn pop_max_node<K: Ord, V>(link: &mut Link<K, V>) -> Option<Box<Node<K, V>>> {
+    let mut cur_link = link;
+    while cur_link.as_ref()?.right.is_some() {
+	let box_node = cur_link.as_mut().unwrap();
+	box_node.size -= 1;
+	cur_link = &mut box_node.right;
+    }
+    let mut node = cur_link.take()?;
+    *cur_link = node.left.take();
+    Some(node)
+}
+
+// Ordered queries
+
+impl<T: Ord> BST<T> {
+    /// The smallest value in the tree.
+    pub fn min(&self) -> Option<&T> {
+	let mut cur = self.root.as_deref()?;
+	while let Some(left) = cur.left.as_deref() {
+	    cur = left;
+	}
+	Some(&cur.key)
+    }
+
+    /// The largest value in the tree.
+    pub fn max(&self) -> Option<&T> {
+	let mut cur = self.root.as_deref()?;
+	while let Some(right) = cur.right.as_deref() {
+	    cur = right;
+	}
+	Some(&cur.key)
+    }
+
+    /// The largest value no greater than `val`.
+    pub fn floor(&self, val: &T) -> Option<&T> {
+	self.last_before(val, true)
+    }
+
+    /// The smallest value no less than `val`.
+    pub fn ceiling(&self, val: &T) -> Option<&T> {
+	self.first_after(val, true)
+    }
+
+    /// The largest value less than `val`, whether or not `val` is in the
+    /// tree.
+    pub fn predecessor(&self, val: &T) -> Option<&T> {
+	self.last_before(val, false)
+    }
+
+    /// The smallest value greater than `val`, whether or not `val` is in
+    /// the tree.
+    pub fn successor(&self, val: &T) -> Option<&T> {
+	self.first_after(val, false)
+    }
+
+    /// The number of values in the tree less than `val`, which is where
+    /// `val` is or would be in the sorted order.
+    pub fn rank(&self, val: &T) -> usize {
+	self.count_before(val, false)
+    }
+
+    /// The `k`th smallest value in the tree, counting from zero, so that
+    /// `select(rank(v)) == Some(v)` for every `v` in the tree.
+    pub fn select(&self, k: usize) -> Option<&T> {
+	let mut k = k;
+	let mut cur_link = self.root.as_deref();
+	while let Some(node) = cur_link {
+	    let left = size(&node.left);
+	    if k < left {
+		cur_link = node.left.as_deref();
+	    } else if k == left {
+		return Some(&node.key);
+	    } else {
+		k -= left + 1;
+		cur_link = node.right.as_deref();
+	    }
+	}
+	None
+    }
+
+    /// An iterator over the values within `range`, in order, e.g.
+    /// `bst.range(3..7)` or `bst.range(..=&x)`.
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+	let (lo, hi) = (range.start_bound(), range.end_bound());
+	let len = match (lo, hi) {
+	    (Bound::Included(lo), Bound::Included(hi)) |
+	    (Bound::Included(lo), Bound::Excluded(hi)) |
+	    (Bound::Excluded(lo), Bound::Included(hi)) |
+	    (Bound::Excluded(lo), Bound::Excluded(hi)) if lo > hi => 0,
+	    _ => {
+		let end = match hi {
+		    Bound::Included(hi) => self.count_before(hi, true),
+		    Bound::Excluded(hi) => self.count_before(hi, false),
+		    Bound::Unbounded => self.len(),
+		};
+		let start = match lo {
+		    Bound::Included(lo) => self.count_before(lo, false),
+		    Bound::Excluded(lo) => self.count_before(lo, true),
+		    Bound::Unbounded => 0,
+		};
+		end.saturating_sub(start)
+	    },
+	};
+	if len == 0 {
+	    return Range(Walk::new(None, 0));
+	}
+
+	// Find the highest node within the range; everything in range is in
+	// its subtree. Then trim its left subtree to the values above `lo`
+	// and its right subtree to those below `hi`.
+	let mut cur_link = self.root.as_deref();
+	while let Some(node) = cur_link {
+	    if !above(lo, &node.key) {
+		cur_link = node.right.as_deref();
+	    } else if !below(hi, &node.key) {
+		cur_link = node.left.as_deref();
+	    } else {
+		break;
+	    }
+	}
+	let top = cur_link.unwrap();
+	let mut pieces = VecDeque::new();
+
+	let mut tail = Vec::new();
+	let mut cur_link = top.left.as_deref();
+	while let Some(node) = cur_link {
+	    if above(lo, &node.key) {
+		tail.extend(node.right.as_deref().map(Piece::Tree));
+		tail.push(Piece::Val((&node.key, &node.val)));
+		cur_link = node.left.as_deref();
+	    } else {
+		cur_link = node.right.as_deref();
+	    }
+	}
+	pieces.extend(tail.into_iter().rev());
+
+	pieces.push_back(Piece::Val((&top.key, &top.val)));
+
+	let mut cur_link = top.right.as_deref();
+	while let Some(node) = cur_link {
+	    if below(hi, &node.key) {
+		pieces.extend(node.left.as_deref().map(Piece::Tree));
+		pieces.push_back(Piece::Val((&node.key, &node.val)));
+		cur_link = node.right.as_deref();
+	    } else {
+		cur_link = node.left.as_deref();
+	    }
+	}
+	Range(Walk { pieces, len })
+    }
+
+    /// The number of values less than `val`, or no greater than it if
+    /// `inclusive`.
+    fn count_before(&self, val: &T, inclusive: bool) -> usize {
+	let mut count = 0;
+	let mut cur_link = self.root.as_deref();
+	while let Some(node) = cur_link {
+	    if node.key < *val || (inclusive && node.key == *val) {
+		count += size(&node.left) + 1;
+		cur_link = node.right.as_deref();
+	    } else {
+		cur_link = node.left.as_deref();
+	    }
+	}
+	count
+    }
+
+    /// The largest value less than `val`, or no greater than it if
+    /// `inclusive`.
+    fn last_before(&self, val: &T, inclusive: bool) -> Option<&T> {
+	let mut best = None;
+	let mut cur_link = self.root.as_deref();
+	while let Some(node) = cur_link {
+	    if node.key < *val || (inclusive && node.key == *val) {
+		best = Some(&node.key);
+		cur_link = node.right.as_deref();
+	    } else {
+		cur_link = node.left.as_deref();
+	    }
+	}
+	best
+    }
+
+    /// The smallest value greater than `val`, or no less than it if
+    /// `inclusive`.
+    fn first_after(&self, val: &T, inclusive: bool) -> Option<&T> {
+	let mut best = None;
+	let mut cur_link = self.root.as_deref();
+	while let Some(node) = cur_link {
+	    if node.key > *val || (inclusive && node.key == *val) {
+		best = Some(&node.key);
+		cur_link = node.left.as_deref();
+	    } else {
+		cur_link = node.right.as_deref();
+	    }
+	}
+	best
+    }
+}
+
+/// Whether `val` is on the inner side of the lower bound `lo`.
+fn above<T: Ord>(lo: Bound<&T>, val: &T) -> bool {
+    match lo {
+	Bound::Included(lo) => val >= lo,
+	Bound::Excluded(lo) => val > lo,
+	Bound::Unbounded => true,
+    }
+}
+
+/// Whether `val` is on the inner side of the upper bound `hi`.
+fn below<T: Ord>(hi: Bound<&T>, val: &T) -> bool {
+    match hi {
+	Bound::Included(hi) => val <= hi,
+	Bound::Excluded(hi) => val < hi,
+	Bound::Unbounded => true,
     }
 }
 
 // Iterators
 
+/// A subtree an iterator has yet to visit, which it can split into its left
+/// subtree, its own value and its right subtree.
+pub(crate) trait Subtree: Sized {
+    type Val;
+    fn split(self) -> (Option<Self>, Self::Val, Option<Self>);
+}
+
+impl<'a, K: Ord, V> Subtree for &'a Node<K, V> {
+    type Val = (&'a K, &'a V);
+    fn split(self) -> (Option<Self>, (&'a K, &'a V), Option<Self>) {
+	(self.left.as_deref(), (&self.key, &self.val), self.right.as_deref())
+    }
+}
+
+impl<'a, K: Ord, V> Subtree for &'a mut Node<K, V> {
+    type Val = (&'a mut K, &'a mut V);
+    fn split(self) -> (Option<Self>, (&'a mut K, &'a mut V), Option<Self>) {
+	let Node { ref mut key, ref mut val, ref mut left, ref mut right, .. } = *self;
+	(left.as_deref_mut(), (key, val), right.as_deref_mut())
+    }
+}
+
+impl<K: Ord, V> Subtree for Box<Node<K, V>> {
+    type Val = (K, V);
+    fn split(self) -> (Option<Self>, (K, V), Option<Self>) {
+	let node = *self;
+	(node.left, (node.key, node.val), node.right)
+    }
+}
+
+enum Piece<N: Subtree> {
+    Val(N::Val),
+    Tree(N),
+}
+
+/// An in-order walk from both ends at once. What is left to visit is kept as
+/// a row of values and whole subtrees; taking from either end splits
+/// subtrees there until a value comes out. Each end only ever holds a path's
+/// worth of pieces, and because the pieces never overlap the same walk
+/// serves shared, mutable and owning iterators alike.
+pub(crate) struct Walk<N: Subtree> {
+    pieces: VecDeque<Piece<N>>,
+    len: usize,
+}
+
+impl<N: Subtree> Walk<N> {
+    /// A walk over the subtree `root`, which holds `len` values.
+    pub(crate) fn new(root: Option<N>, len: usize) -> Self {
+	Walk { pieces: root.into_iter().map(Piece::Tree).collect(), len }
+    }
+
+    pub(crate) fn next(&mut self) -> Option<N::Val> {
+	loop {
+	    match self.pieces.pop_front()? {
+		Piece::Val(val) => {
+		    self.len -= 1;
+		    return Some(val);
+		},
+		Piece::Tree(tree) => {
+		    let (left, val, right) = tree.split();
+		    if let Some(right) = right {
+			self.pieces.push_front(Piece::Tree(right));
+		    }
+		    self.pieces.push_front(Piece::Val(val));
+		    if let Some(left) = left {
+			self.pieces.push_front(Piece::Tree(left));
+		    }
+		},
+	    }
+	}
+    }
+
+    pub(crate) fn next_back(&mut self) -> Option<N::Val> {
+	loop {
+	    match self.pieces.pop_back()? {
+		Piece::Val(val) => {
+		    self.len -= 1;
+		    return Some(val);
+		},
+		Piece::Tree(tree) => {
+		    let (left, val, right) = tree.split();
+		    if let Some(left) = left {
+			self.pieces.push_back(Piece::Tree(left));
+		    }
+		    self.pieces.push_back(Piece::Val(val));
+		    if let Some(right) = right {
+			self.pieces.push_back(Piece::Tree(right));
+		    }
+		},
+	    }
+	}
+    }
+
+    pub(crate) fn len(&self) -> usize {
+	self.len
+    }
+}
+
 // IntoIter
-pub struct IntoIter<T: Ord>(BST<T>);
+pub struct IntoIter<T: Ord>(Walk<Box<Node<T, ()>>>);
 
-impl<T: Ord> BST<T> {
-    pub fn into_iter(self) -> IntoIter<T> {
-	IntoIter(self)
+impl<T: Ord> IntoIterator for BST<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> IntoIter<T> {
+	let len = self.len();
+	IntoIter(Walk::new(self.root.take(), len))
     }
 }
 
 impl<T: Ord> Iterator for IntoIter<T> {
     type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
-	self.0.root.take().map(|box_node| {
-	    let node = *box_node;
-	    self.0.root = node.right;
-	    node.val
-	})
+	self.0.next().map(|(val, _)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
     }
 }
 
-//Iter
-pub struct Iter<'a, T: 'a + Ord> {
-    next: Option<&'a Node<T>>,
+impl<T: Ord> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back().map(|(val, _)| val)
+    }
 }
 
+impl<T: Ord> ExactSizeIterator for IntoIter<T> {}
+
+//Iter
+pub struct Iter<'a, T: 'a + Ord>(Walk<&'a Node<T, ()>>);
+
 impl<T: Ord> BST<T> {
-    pub fn iter<'a>(&'a self) -> Iter<'a, T> {
-	Iter { next: self.root.as_ref().map(|box_node| &**box_node) }
+    pub fn iter(&self) -> Iter<'_, T> {
+	Iter(Walk::new(self.root.as_deref(), self.len()))
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a BST<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+	self.iter()
     }
 }
 
 impl<'a, T: Ord> Iterator for Iter<'a, T> {
     type Item = &'a T;
     fn next(&mut self) -> Option<Self::Item> {
-	self.next.map(|node| {
-	    self.next = node.right.as_ref().map(|box_node| &**box_node);
-	    &node.val
-	})
+	self.0.next().map(|(val, _)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
     }
 }
 
-//IterMut
-pub struct IterMut<'a, T: 'a + Ord> {
-    next: Option<&'a mut Node<T>>,
+impl<'a, T: Ord> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back().map(|(val, _)| val)
+    }
 }
 
+impl<'a, T: Ord> ExactSizeIterator for Iter<'a, T> {}
+
+//IterMut
+pub struct IterMut<'a, T: 'a + Ord>(Walk<&'a mut Node<T, ()>>);
+
 impl<T: Ord> BST<T> {
-    pub fn iter_mut<'a>(&'a mut self) -> IterMut<'a, T> {
-	IterMut { next: self.root.as_mut().map(|box_node| &mut **box_node) }
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+	let len = self.len();
+	IterMut(Walk::new(self.root.as_deref_mut(), len))
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a mut BST<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+	self.iter_mut()
     }
 }
 
 impl<'a, T: Ord> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
     fn next(&mut self) -> Option<Self::Item> {
-	self.next.take().map(|node| {
-	    self.next = node.right.as_mut().map(|box_node| &mut **box_node);
-	    &mut node.val
-	})
+	self.0.next().map(|(val, _)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back().map(|(val, _)| val)
     }
 }
 
+impl<'a, T: Ord> ExactSizeIterator for IterMut<'a, T> {}
+
+//Range
+pub struct Range<'a, T: 'a + Ord>(Walk<&'a Node<T, ()>>);
+
+impl<'a, T: Ord> Iterator for Range<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+	self.0.next().map(|(val, _)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for Range<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back().map(|(val, _)| val)
+    }
+}
+
+impl<'a, T: Ord> ExactSizeIterator for Range<'a, T> {}
+
 #[cfg(test)]
 mod test {
-    use super::BST;
+    use std::collections::BTreeSet;
+    use std::ops::Bound;
+    use std::thread;
+
+    use proptest::prelude::*;
+
+    use super::{Link, BST};
+
+    /// Check that every node's size counts its subtree, and return the size.
+    fn check_sizes<T: Ord>(link: &Link<T, ()>) -> usize {
+	match *link {
+	    Some(ref box_node) => {
+		let size = 1 + check_sizes(&box_node.left) + check_sizes(&box_node.right);
+		assert_eq!(box_node.size, size);
+		size
+	    },
+	    None => 0,
+	}
+    }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn basics() {
         let mut bst = BST::new();
 
@@ -203,4 +765,199 @@ mod test {
 	assert_eq!(iter.next(), Some(&mut 2));
 	assert_eq!(iter.next(), Some(&mut 3));
     }
+
+    #[test]
+    fn unbalanced() {
+	// Descending inserts lean all the way left, and a zigzag alternates.
+	let mut left = BST::new();
+	for val in (0..1000).rev() {
+	    left.insert(val);
+	}
+	assert!(left.iter().cloned().eq(0..1000));
+	assert!(left.iter().rev().cloned().eq((0..1000).rev()));
+
+	// Too deep to drop recursively on a small stack.
+	thread::Builder::new().stack_size(64 * 1024).spawn(|| {
+	    let mut right = BST::new();
+	    for val in 0..5000 {
+		right.insert(val);
+	    }
+	    assert_eq!(right.max(), Some(&4999));
+	}).unwrap().join().unwrap();
+
+	let mut zigzag = BST::new();
+	for &val in &[10, 0, 9, 1, 8, 2, 7, 3, 6, 4, 5] {
+	    zigzag.insert(val);
+	}
+	let mut iter = zigzag.iter_mut();
+	assert_eq!(iter.len(), 11);
+	assert_eq!(iter.next(), Some(&mut 0));
+	assert_eq!(iter.next_back(), Some(&mut 10));
+	assert!(iter.map(|val| *val).eq(1..10));
+	assert_eq!(zigzag.len(), 11);
+	assert!(zigzag.search(5));
+	assert!(zigzag.into_iter().eq(0..11));
+    }
+
+    #[test]
+    fn remove() {
+	let mut bst = BST::new();
+	for &val in &[5, 3, 8, 1, 4, 7, 9, 6] {
+	    bst.insert(val);
+	}
+
+	// A leaf, a node with one child and a node with two.
+	assert_eq!(bst.remove(&1), Some(1));
+	assert_eq!(bst.remove(&7), Some(7));
+	assert_eq!(bst.remove(&5), Some(5));
+	assert_eq!(bst.remove(&5), None);
+	assert_eq!(bst.len(), 5);
+	for &val in &[3, 4, 6, 8, 9] {
+	    assert!(bst.search(val));
+	}
+
+	assert_eq!(bst.pop_min(), Some(3));
+	assert_eq!(bst.pop_max(), Some(9));
+	assert_eq!(bst.pop_min(), Some(4));
+	assert_eq!(bst.pop_max(), Some(8));
+	assert_eq!(bst.pop_max(), Some(6));
+	assert_eq!(bst.pop_min(), None);
+	assert!(bst.is_empty());
+    }
+
+    #[test]
+    fn ordered_queries() {
+	let mut bst = BST::new();
+	for &val in &[50, 30, 70, 20, 40, 60, 80] {
+	    bst.insert(val);
+	}
+	assert_eq!((bst.min(), bst.max()), (Some(&20), Some(&80)));
+	assert_eq!(bst.floor(&45), Some(&40));
+	assert_eq!(bst.floor(&40), Some(&40));
+	assert_eq!(bst.floor(&10), None);
+	assert_eq!(bst.ceiling(&45), Some(&50));
+	assert_eq!(bst.ceiling(&90), None);
+	assert_eq!(bst.predecessor(&40), Some(&30));
+	assert_eq!(bst.successor(&40), Some(&50));
+	assert_eq!(bst.successor(&80), None);
+
+	assert_eq!(bst.rank(&20), 0);
+	assert_eq!(bst.rank(&55), 4);
+	assert_eq!(bst.rank(&100), 7);
+	assert_eq!(bst.select(0), Some(&20));
+	assert_eq!(bst.select(4), Some(&60));
+	assert_eq!(bst.select(7), None);
+
+	assert_eq!(bst.range(35..70).collect::<Vec<_>>(), vec![&40, &50, &60]);
+	assert_eq!(bst.range(..=40).rev().collect::<Vec<_>>(), vec![&40, &30, &20]);
+	assert_eq!(bst.range(60..).len(), 3);
+	assert_eq!(bst.range(41..50).next(), None);
+
+	let empty: BST<i32> = BST::new();
+	assert_eq!((empty.min(), empty.max(), empty.select(0)), (None, None, None));
+	assert_eq!(empty.range(..).next(), None);
+    }
+
+    #[derive(Clone, Debug)]
+    enum Op {
+	Insert(u8),
+	Remove(u8),
+	PopMin,
+	PopMax,
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+	prop_oneof![
+	    any::<u8>().prop_map(Op::Insert),
+	    any::<u8>().prop_map(Op::Remove),
+	    Just(Op::PopMin),
+	    Just(Op::PopMax),
+	]
+    }
+
+    proptest! {
+	#[test]
+	fn matches_btreeset(ops in prop::collection::vec(op(), 0..200)) {
+	    let mut bst = BST::new();
+	    let mut set = BTreeSet::new();
+	    for op in ops {
+		match op {
+		    Op::Insert(val) => prop_assert_eq!(bst.insert(val), set.insert(val)),
+		    Op::Remove(val) => prop_assert_eq!(bst.remove(&val), set.take(&val)),
+		    Op::PopMin => prop_assert_eq!(bst.pop_min(), set.pop_first()),
+		    Op::PopMax => prop_assert_eq!(bst.pop_max(), set.pop_last()),
+		}
+		prop_assert_eq!(bst.len(), set.len());
+		check_sizes(&bst.root);
+	    }
+	    for val in 0..=255 {
+		prop_assert_eq!(bst.search(val), set.contains(&val));
+	    }
+	}
+
+	#[test]
+	fn queries_match_btreeset(vals in prop::collection::vec(0..200u16, 0..100),
+				  lo in 0..210u16, hi in 0..210u16, lo_incl in any::<bool>(), hi_incl in any::<bool>()) {
+	    let mut bst = BST::new();
+	    for &val in &vals {
+		bst.insert(val);
+	    }
+	    let set: BTreeSet<u16> = vals.into_iter().collect();
+	    prop_assert_eq!(bst.min(), set.iter().next());
+	    prop_assert_eq!(bst.max(), set.iter().next_back());
+	    for val in 0..210 {
+		prop_assert_eq!(bst.floor(&val), set.range(..=val).next_back());
+		prop_assert_eq!(bst.ceiling(&val), set.range(val..).next());
+		prop_assert_eq!(bst.predecessor(&val), set.range(..val).next_back());
+		prop_assert_eq!(bst.successor(&val), set.range((Bound::Excluded(val), Bound::Unbounded)).next());
+		prop_assert_eq!(bst.rank(&val), set.range(..val).count());
+	    }
+	    for k in 0..=set.len() {
+		prop_assert_eq!(bst.select(k), set.iter().nth(k));
+	    }
+
+	    let bound = |val, incl| if incl { Bound::Included(val) } else { Bound::Excluded(val) };
+	    let bounds = (bound(lo, lo_incl), bound(hi, hi_incl));
+	    let range = bst.range(bounds);
+	    // `BTreeSet::range` panics on backwards ranges; ours are empty.
+	    if lo < hi || (lo == hi && lo_incl && hi_incl) {
+		prop_assert_eq!(range.len(), set.range(bounds).count());
+		prop_assert!(range.eq(set.range(bounds)));
+		prop_assert!(bst.range(bounds).rev().eq(set.range(bounds).rev()));
+	    } else {
+		prop_assert_eq!(range.count(), 0);
+	    }
+	    prop_assert!(bst.range(..hi).eq(set.range(..hi)));
+	    prop_assert!(bst.range(lo..).eq(set.range(lo..)));
+	}
+
+	#[test]
+	fn iterates_in_order(vals in prop::collection::vec(any::<u16>(), 0..200), fronts in any::<u64>()) {
+	    let mut bst = BST::new();
+	    for &val in &vals {
+		bst.insert(val);
+	    }
+	    let sorted: BTreeSet<u16> = vals.into_iter().collect();
+	    prop_assert!(bst.iter().eq(sorted.iter()));
+	    prop_assert!(bst.iter().rev().eq(sorted.iter().rev()));
+	    prop_assert_eq!(bst.iter_mut().len(), sorted.len());
+
+	    // Take from both ends, choosing each end by a bit of `fronts`,
+	    // and check the two ends meet without skipping or repeating.
+	    let mut iter = bst.into_iter();
+	    let mut expected = sorted.into_iter();
+	    for i in 0.. {
+		prop_assert_eq!(iter.len(), expected.len());
+		let (got, want) = if fronts >> (i % 64) & 1 == 1 {
+		    (iter.next(), expected.next())
+		} else {
+		    (iter.next_back(), expected.next_back())
+		};
+		prop_assert_eq!(got, want);
+		if got.is_none() {
+		    break;
+		}
+	    }
+	}
+    }
 }
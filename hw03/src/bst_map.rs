@@ -0,0 +1,477 @@
+//! An ordered map on the same unbalanced tree as `BST`, sharing its nodes,
+//! which here hold a key and its value, and the helpers that search, insert
+//! into and remove from it. Its API follows `BTreeMap`'s so that it can
+//! stand in for one.
+
+use std::borrow::Borrow;
+use std::iter::FromIterator;
+use std::ops::Index;
+
+use bst::{self, find, find_mut, free, locate, size, Link, Node, Vacancy, Walk};
+
+#[derive(Debug)]
+pub struct BstMap<K: Ord, V> {
+    root: Link<K, V>,
+}
+
+impl<K: Ord, V> BstMap<K, V> {
+    pub fn new() -> Self {
+        BstMap { root: None }
+    }
+
+    /// The number of keys in the map.
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+}
+
+impl<K: Ord, V> Default for BstMap<K, V> {
+    fn default() -> Self {
+        BstMap::new()
+    }
+}
+
+impl<K: Ord, V> Drop for BstMap<K, V> {
+    fn drop(&mut self) {
+	free(&mut self.root);
+    }
+}
+
+impl<K: Ord, V> BstMap<K, V> {
+    /// Map `key` to `val`, returning the value it was mapped to before.
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+	match self.entry(key) {
+	    Entry::Occupied(mut entry) => Some(entry.insert(val)),
+	    Entry::Vacant(entry) => {
+		entry.insert(val);
+		None
+	    },
+	}
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V> where K: Borrow<Q>, Q: ?Sized + Ord {
+	find(&self.root, key).map(|node| &node.val)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V> where K: Borrow<Q>, Q: ?Sized + Ord {
+	find_mut(&mut self.root, key).map(|node| &mut node.val)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool where K: Borrow<Q>, Q: ?Sized + Ord {
+	self.get(key).is_some()
+    }
+
+    /// Remove `key` from the map, returning the value it was mapped to. As
+    /// in `BST`, a node with two children is replaced by its in-order
+    /// successor.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V> where K: Borrow<Q>, Q: ?Sized + Ord {
+	bst::remove(&mut self.root, key).map(|node| node.val)
+    }
+
+    /// The entry for `key`, for looking it up and filling it in at once.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+	match locate(&mut self.root, &key) {
+	    Ok(node) => Entry::Occupied(OccupiedEntry { node }),
+	    Err(vacancy) => Entry::Vacant(VacantEntry { key, vacancy }),
+	}
+    }
+}
+
+// Entries
+
+pub enum Entry<'a, K: 'a + Ord, V: 'a> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, K: 'a + Ord, V: 'a> {
+    node: &'a mut Node<K, V>,
+}
+
+/// A key that is not in the map, with the place in the tree it would go.
+pub struct VacantEntry<'a, K: 'a + Ord, V: 'a> {
+    key: K,
+    vacancy: Vacancy<'a, K, V>,
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    pub fn key(&self) -> &K {
+	match *self {
+	    Entry::Occupied(ref entry) => entry.key(),
+	    Entry::Vacant(ref entry) => entry.key(),
+	}
+    }
+
+    /// The entry's value, filling it in with `default` if it has none.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+	self.or_insert_with(|| default)
+    }
+
+    /// The entry's value, filling it in by calling `default` if it has none.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+	match self {
+	    Entry::Occupied(entry) => entry.into_mut(),
+	    Entry::Vacant(entry) => entry.insert(default()),
+	}
+    }
+}
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+	&self.node.key
+    }
+
+    pub fn get(&self) -> &V {
+	&self.node.val
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+	&mut self.node.val
+    }
+
+    /// The value, borrowed for as long as the map was.
+    pub fn into_mut(self) -> &'a mut V {
+	&mut self.node.val
+    }
+
+    /// Replace the value, returning the old one.
+    pub fn insert(&mut self, val: V) -> V {
+	::std::mem::replace(&mut self.node.val, val)
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+	&self.key
+    }
+
+    /// Add the key to the map with `val`.
+    pub fn insert(self, val: V) -> &'a mut V {
+	&mut self.vacancy.fill(self.key, val).val
+    }
+}
+
+// Trait impls
+
+impl<K, V, Q> Index<&Q> for BstMap<K, V> where K: Ord + Borrow<Q>, Q: ?Sized + Ord {
+    type Output = V;
+
+    /// The value for `key`. Panics if the key is not in the map.
+    fn index(&self, key: &Q) -> &V {
+	self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for BstMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+	let mut map = BstMap::new();
+	map.extend(iter);
+	map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for BstMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+	for (key, val) in iter {
+	    self.insert(key, val);
+	}
+    }
+}
+
+// Iterators, in key order.
+
+// IntoIter
+pub struct IntoIter<K: Ord, V>(Walk<Box<Node<K, V>>>);
+
+impl<K: Ord, V> IntoIterator for BstMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(mut self) -> IntoIter<K, V> {
+	let len = self.len();
+	IntoIter(Walk::new(self.root.take(), len))
+    }
+}
+
+impl<K: Ord, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+	self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<K: Ord, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back()
+    }
+}
+
+impl<K: Ord, V> ExactSizeIterator for IntoIter<K, V> {}
+
+//Iter
+pub struct Iter<'a, K: 'a + Ord, V: 'a>(Walk<&'a Node<K, V>>);
+
+impl<K: Ord, V> BstMap<K, V> {
+    pub fn iter(&self) -> Iter<'_, K, V> {
+	Iter(Walk::new(self.root.as_deref(), self.len()))
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a BstMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+	self.iter()
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+	self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<'a, K: Ord, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back()
+    }
+}
+
+impl<'a, K: Ord, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+//IterMut
+pub struct IterMut<'a, K: 'a + Ord, V: 'a>(Walk<&'a mut Node<K, V>>);
+
+impl<K: Ord, V> BstMap<K, V> {
+    /// Iterate with each value mutable. The keys stay shared, since
+    /// changing one could put it out of order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+	let len = self.len();
+	IterMut(Walk::new(self.root.as_deref_mut(), len))
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a mut BstMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+	self.iter_mut()
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+    fn next(&mut self) -> Option<Self::Item> {
+	self.0.next().map(|(key, val)| (&*key, val))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	(self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<'a, K: Ord, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back().map(|(key, val)| (&*key, val))
+    }
+}
+
+impl<'a, K: Ord, V> ExactSizeIterator for IterMut<'a, K, V> {}
+
+//Keys
+pub struct Keys<'a, K: 'a + Ord, V: 'a>(Iter<'a, K, V>);
+
+impl<K: Ord, V> BstMap<K, V> {
+    pub fn keys(&self) -> Keys<'_, K, V> {
+	Keys(self.iter())
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+    fn next(&mut self) -> Option<Self::Item> {
+	self.0.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	self.0.size_hint()
+    }
+}
+
+impl<'a, K: Ord, V> DoubleEndedIterator for Keys<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a, K: Ord, V> ExactSizeIterator for Keys<'a, K, V> {}
+
+//Values
+pub struct Values<'a, K: 'a + Ord, V: 'a>(Iter<'a, K, V>);
+
+impl<K: Ord, V> BstMap<K, V> {
+    pub fn values(&self) -> Values<'_, K, V> {
+	Values(self.iter())
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+    fn next(&mut self) -> Option<Self::Item> {
+	self.0.next().map(|(_, val)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+	self.0.size_hint()
+    }
+}
+
+impl<'a, K: Ord, V> DoubleEndedIterator for Values<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+	self.0.next_back().map(|(_, val)| val)
+    }
+}
+
+impl<'a, K: Ord, V> ExactSizeIterator for Values<'a, K, V> {}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use proptest::prelude::*;
+
+    use super::{BstMap, Entry};
+
+    #[test]
+    fn basics() {
+	let mut map = BstMap::new();
+	assert_eq!(map.insert(2, "two"), None);
+	assert_eq!(map.insert(1, "one"), None);
+	assert_eq!(map.insert(3, "three"), None);
+	assert_eq!(map.insert(2, "deux"), Some("two"));
+	assert_eq!(map.len(), 3);
+
+	assert_eq!(map.get(&2), Some(&"deux"));
+	assert_eq!(map.get(&4), None);
+	*map.get_mut(&1).unwrap() = "un";
+	assert_eq!(map[&1], "un");
+	assert!(map.contains_key(&3));
+
+	assert_eq!(map.remove(&2), Some("deux"));
+	assert_eq!(map.remove(&2), None);
+	assert!(!map.contains_key(&2));
+	assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![(1, "un"), (3, "three")]);
+    }
+
+    #[test]
+    fn entries() {
+	let mut counts = BstMap::new();
+	for word in "the cat and the hat and the bat".split(' ') {
+	    *counts.entry(word.to_string()).or_insert(0) += 1;
+	}
+	assert_eq!(counts["the"], 3);
+	assert_eq!(counts["and"], 2);
+	assert_eq!(counts["cat"], 1);
+
+	let mut calls = 0;
+	*counts.entry("the".to_string()).or_insert_with(|| { calls += 1; 0 }) += 1;
+	counts.entry("rat".to_string()).or_insert_with(|| { calls += 1; 0 });
+	assert_eq!(calls, 1);
+	assert_eq!(counts["the"], 4);
+	assert_eq!(counts.len(), 6);
+
+	match counts.entry("bat".to_string()) {
+	    Entry::Occupied(mut entry) => assert_eq!(entry.insert(7), 1),
+	    Entry::Vacant(_) => panic!("bat should be in the map"),
+	}
+	assert_eq!(counts.entry("cow".to_string()).key(), "cow");
+	assert!(!counts.contains_key("cow"));
+    }
+
+    #[test]
+    fn from_iter_and_extend() {
+	let mut map: BstMap<_, _> = vec![(3, 'c'), (1, 'a'), (2, 'b')].into_iter().collect();
+	map.extend(vec![(4, 'd'), (1, 'A')]);
+	let pairs: Vec<_> = map.iter().map(|(&k, &v)| (k, v)).collect();
+	assert_eq!(pairs, vec![(1, 'A'), (2, 'b'), (3, 'c'), (4, 'd')]);
+	assert_eq!(map.iter().next_back(), Some((&4, &'d')));
+    }
+
+    #[test]
+    fn iter_mut_keys_values() {
+	let mut map: BstMap<_, _> = vec![(2, 20), (1, 10), (3, 30)].into_iter().collect();
+	for (&key, val) in map.iter_mut() {
+	    *val += key;
+	}
+	for (_, val) in &mut map {
+	    *val *= 2;
+	}
+	assert_eq!(map.iter_mut().next_back(), Some((&3, &mut 66)));
+	assert_eq!(map.keys().cloned().collect::<Vec<_>>(), vec![1, 2, 3]);
+	assert_eq!(map.values().cloned().collect::<Vec<_>>(), vec![22, 44, 66]);
+	assert_eq!(map.values().next_back(), Some(&66));
+	assert_eq!(map.keys().len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "no entry found for key")]
+    fn index_missing_key() {
+	let map: BstMap<i32, i32> = BstMap::new();
+	let _ = map[&1];
+    }
+
+    #[derive(Clone, Debug)]
+    enum Op {
+	Insert(u8, u32),
+	Remove(u8),
+	Bump(u8),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+	prop_oneof![
+	    (any::<u8>(), any::<u32>()).prop_map(|(k, v)| Op::Insert(k, v)),
+	    any::<u8>().prop_map(Op::Remove),
+	    any::<u8>().prop_map(Op::Bump),
+	]
+    }
+
+    proptest! {
+	#[test]
+	fn matches_btreemap(ops in prop::collection::vec(op(), 0..200)) {
+	    let mut map = BstMap::new();
+	    let mut expected = BTreeMap::new();
+	    for op in ops {
+		match op {
+		    Op::Insert(k, v) => prop_assert_eq!(map.insert(k, v), expected.insert(k, v)),
+		    Op::Remove(k) => prop_assert_eq!(map.remove(&k), expected.remove(&k)),
+		    Op::Bump(k) => {
+			*map.entry(k).or_insert(0) += 1;
+			*expected.entry(k).or_insert(0) += 1;
+		    },
+		}
+		prop_assert_eq!(map.len(), expected.len());
+	    }
+	    prop_assert!(map.iter().eq(expected.iter()));
+	    prop_assert!(map.keys().eq(expected.keys()));
+	    prop_assert!(map.values().eq(expected.values()));
+	    for k in 0..=255 {
+		prop_assert_eq!(map.get(&k), expected.get(&k));
+	    }
+	}
+    }
+}
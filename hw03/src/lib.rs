@@ -1 +1,6 @@
-mod bst;
+#[cfg(test)]
+extern crate proptest;
+
+pub mod avl;
+pub mod bst;
+pub mod bst_map;
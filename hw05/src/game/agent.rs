@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+
+use rand::{Rng, StdRng};
+use rustc_serialize::json::Json;
+
+use super::board::{self, Board, Result};
+use super::difficulty::Difficulty;
+use super::event::Sense;
+use super::player::{Command, Player};
+use super::room::RoomId;
+
+/// Games that run longer than this many turns are abandoned.
+const MAX_TURNS: usize = 1000;
+
+/// An automated player. Agents look at the board only through what the
+/// player could see: where they are, the exits, and the warnings there.
+pub trait Agent {
+    fn choose(&mut self, board: &Board, player: &Player) -> Command;
+}
+
+/// The command to walk through the `i`th exit of the player's room.
+fn go_exit(board: &Board, player: &Player, room: RoomId) -> Command {
+    let i = board.neighbors(player.location).iter().position(|&r| r == room).unwrap();
+    Command::Go((i + 1).to_string())
+}
+
+/// The command to shoot into the `i`th exit of the player's room.
+fn shoot_exit(board: &Board, player: &Player, room: RoomId) -> Command {
+    let i = board.neighbors(player.location).iter().position(|&r| r == room).unwrap();
+    Command::Shoot((i + 1).to_string())
+}
+
+/// Wanders from room to room at random, shoots at random when it smells the
+/// wumpus, and fights any monster it meets.
+pub struct RandomWalker {
+    rng: StdRng,
+}
+
+impl RandomWalker {
+    /// A walker whose choices are drawn from `seed`.
+    pub fn new(seed: u64) -> RandomWalker {
+        RandomWalker { rng: board::seeded_rng(seed) }
+    }
+}
+
+impl Agent for RandomWalker {
+    fn choose(&mut self, board: &Board, player: &Player) -> Command {
+        if !board.room(player.location).monsters.is_empty() {
+            return Command::Fight;
+        }
+        let rng = &mut self.rng;
+        let room = match rng.choose(board.neighbors(player.location)) {
+            Some(&room) => room,
+            None => return Command::Wait,
+        };
+        if board.senses(player.location).contains(&Sense::Wumpus) && rng.gen() {
+            shoot_exit(board, player, room)
+        } else {
+            go_exit(board, player, room)
+        }
+    }
+}
+
+/// Explores the nearest unvisited room it knows to be safe, working out from
+/// the warnings it has felt where the pits and the wumpus must be. It shoots
+/// once it has narrowed the wumpus down to a single room next to it, and
+/// fights any monster that blocks its way.
+pub struct GreedyExplorer {
+    visited: HashSet<RoomId>,
+    no_wumpus: HashSet<RoomId>,
+    no_pit: HashSet<RoomId>,
+    /// Rooms the wumpus might be in, if it has been smelled since it last moved.
+    suspects: Option<HashSet<RoomId>>,
+    shot: bool,
+    /// Breaks ties between equally good moves.
+    rng: StdRng,
+}
+
+impl GreedyExplorer {
+    /// An explorer whose tie-breaking is drawn from `seed`.
+    pub fn new(seed: u64) -> GreedyExplorer {
+        GreedyExplorer {
+            visited: HashSet::new(),
+            no_wumpus: HashSet::new(),
+            no_pit: HashSet::new(),
+            suspects: None,
+            shot: false,
+            rng: board::seeded_rng(seed),
+        }
+    }
+
+    fn safe(&self, room: RoomId) -> bool {
+        self.no_wumpus.contains(&room) && self.no_pit.contains(&room)
+    }
+
+    /// Take in what the player can sense from where they stand.
+    fn observe(&mut self, board: &Board, player: &Player) {
+        if self.shot {
+            // A missed arrow startles the wumpus, so what we knew about it is stale.
+            self.no_wumpus.clear();
+            self.suspects = None;
+            self.shot = false;
+        }
+
+        let here = player.location;
+        self.visited.insert(here);
+        self.no_wumpus.insert(here);
+        self.no_pit.insert(here);
+
+        let senses = board.senses(here);
+        let neighbors = board.neighbors(here);
+        if senses.contains(&Sense::Wumpus) {
+            let candidates: HashSet<RoomId> = neighbors.iter()
+                .cloned()
+                .filter(|r| !self.no_wumpus.contains(r))
+                .collect();
+            self.suspects = Some(match self.suspects.take() {
+                Some(old) => {
+                    let both: HashSet<RoomId> = old.intersection(&candidates).cloned().collect();
+                    if both.is_empty() { candidates } else { both }
+                },
+                None => candidates,
+            });
+        } else {
+            self.no_wumpus.extend(neighbors.iter().cloned());
+            if let Some(ref mut suspects) = self.suspects {
+                for r in neighbors {
+                    suspects.remove(r);
+                }
+            }
+        }
+        if !senses.contains(&Sense::Draft) {
+            self.no_pit.extend(neighbors.iter().cloned());
+        }
+    }
+
+    /// The first step along the shortest path through visited rooms to a room
+    /// next to an unvisited safe one.
+    fn step_to_frontier(&self, board: &Board, from: RoomId) -> Option<RoomId> {
+        let path = board.find_path(from, |r| self.safe(r), |r| !self.visited.contains(&r))?;
+        path.first().cloned()
+    }
+}
+
+impl Agent for GreedyExplorer {
+    fn choose(&mut self, board: &Board, player: &Player) -> Command {
+        self.observe(board, player);
+        let here = player.location;
+        // Monsters block the way, so there is nothing for it but to fight.
+        if !board.room(here).monsters.is_empty() {
+            return Command::Fight;
+        }
+        let neighbors = board.neighbors(here);
+
+        // A certain shot.
+        if let Some(ref suspects) = self.suspects {
+            if suspects.len() == 1 {
+                let target = *suspects.iter().next().unwrap();
+                if neighbors.contains(&target) {
+                    self.shot = true;
+                    return shoot_exit(board, player, target);
+                }
+            }
+        }
+
+        // The nearest safe room nobody has explored yet.
+        if let Some(room) = self.step_to_frontier(board, here) {
+            return go_exit(board, player, room);
+        }
+
+        // Nowhere safe left: gamble an arrow if there are some to spare.
+        if board.senses(here).contains(&Sense::Wumpus) && player.arrows > 1 {
+            let targets: Vec<RoomId> = neighbors.iter()
+                .cloned()
+                .filter(|r| self.suspects.as_ref().is_none_or(|s| s.contains(r)))
+                .collect();
+            if let Some(&target) = self.rng.choose(&targets) {
+                self.shot = true;
+                return shoot_exit(board, player, target);
+            }
+        }
+
+        // Otherwise risk the least suspicious unvisited neighbor.
+        let risky: Vec<RoomId> = neighbors.iter()
+            .cloned()
+            .filter(|r| !self.visited.contains(r))
+            .collect();
+        let least_risky: Vec<RoomId> = risky.iter()
+            .cloned()
+            .filter(|r| self.no_wumpus.contains(r) || self.no_pit.contains(r))
+            .collect();
+        let rng = &mut self.rng;
+        let choice = rng.choose(&least_risky).or_else(|| rng.choose(&risky)).or_else(|| rng.choose(neighbors));
+        match choice {
+            Some(&room) => go_exit(board, player, room),
+            None => Command::Wait,
+        }
+    }
+}
+
+/// Play one game on `board` with `agent` until it ends or runs too long.
+pub fn play<A: Agent>(board: &mut Board, agent: &mut A) -> Player {
+    let (mut player, _) = Player::spawn(board, Difficulty::default());
+    for _ in 0..MAX_TURNS {
+        if player.won || player.hp <= 0 || player.arrows <= 0 {
+            break;
+        }
+        let cmd = agent.choose(board, &player);
+        if player.act(board, cmd).is_err() {
+            break;
+        }
+    }
+    player
+}
+
+/// The combined results of many games.
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+    pub games: usize,
+    pub wins: usize,
+    pub gold: i64,
+    pub hp: i64,
+}
+
+impl Stats {
+    pub fn win_rate(&self) -> f64 {
+        self.per_game(self.wins as i64)
+    }
+
+    pub fn avg_gold(&self) -> f64 {
+        self.per_game(self.gold)
+    }
+
+    pub fn avg_hp(&self) -> f64 {
+        self.per_game(self.hp)
+    }
+
+    /// `total` averaged over the games played, or zero if there were none.
+    fn per_game(&self, total: i64) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            total as f64 / self.games as f64
+        }
+    }
+}
+
+/// Play `games` games of `level`, each on a freshly built board with a new
+/// agent from `new_agent`. The board and agent of every game are seeded
+/// from `seed`, so the same seed always gives the same results.
+pub fn simulate<A, F>(level: &Json, games: usize, seed: u64, mut new_agent: F) -> Result<Stats>
+    where A: Agent, F: FnMut(u64) -> A
+{
+    let mut seeds = board::seeded_rng(seed);
+    let mut stats = Stats::default();
+    for _ in 0..games {
+        let mut board = Board::from_json_seeded(level, seeds.gen())?;
+        let player = play(&mut board, &mut new_agent(seeds.gen()));
+        stats.games += 1;
+        if player.won {
+            stats.wins += 1;
+        }
+        stats.gold += player.gold as i64;
+        stats.hp += player.hp.max(0) as i64;
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod test {
+    use rustc_serialize::json::Json;
+
+    use super::{play, simulate, GreedyExplorer, RandomWalker};
+    use super::super::board::Board;
+
+    const LEVEL: &str = r#"{"rooms": [{"name": "Entry", "curios": []},
+                                       {"name": "Hall", "curios": [{"chest": 10}]},
+                                       {"name": "Hole", "curios": [], "pit": true},
+                                       {"name": "Lair", "curios": [], "wumpus": true}],
+                            "halls": [[0, 1], [0, 2], [1, 3]]}"#;
+
+    #[test]
+    fn greedy_explorer_wins() {
+        // Nothing is sensed in Entry, so Hall is safe. From Hall the wumpus
+        // can only be in Lair.
+        let level = r#"{"rooms": [{"name": "Entry", "curios": []},
+                                  {"name": "Hall", "curios": [{"chest": 10}]},
+                                  {"name": "Lair", "curios": [], "wumpus": true}],
+                        "halls": [[0, 1], [1, 2]]}"#;
+        let mut board = Board::build_board(&mut level.as_bytes()).unwrap();
+        let player = play(&mut board, &mut GreedyExplorer::new(1));
+        assert!(player.won);
+        assert_eq!(player.gold, 10);
+        assert_eq!(player.arrows, 4);
+    }
+
+    #[test]
+    fn simulate_counts_games() {
+        let level = Json::from_str(LEVEL).unwrap();
+        let stats = simulate(&level, 50, 3, GreedyExplorer::new).unwrap();
+        assert_eq!(stats.games, 50);
+        assert!(stats.wins > 0);
+        assert_eq!(simulate(&level, 50, 3, GreedyExplorer::new).unwrap(), stats);
+        let stats = simulate(&level, 50, 3, RandomWalker::new).unwrap();
+        assert_eq!(stats.games, 50);
+        assert!(stats.avg_hp() >= 0.0 && stats.avg_hp() <= 25.0);
+        assert_eq!(simulate(&level, 50, 3, RandomWalker::new).unwrap(), stats);
+
+        let stats = simulate(&level, 0, 3, GreedyExplorer::new).unwrap();
+        assert_eq!((stats.win_rate(), stats.avg_gold(), stats.avg_hp()), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn simulate_wins_a_safe_level() {
+        // As in `greedy_explorer_wins`, every game can be won, whatever the
+        // seed.
+        let level = Json::from_str(r#"{"rooms": [{"name": "Entry", "curios": []},
+                                                 {"name": "Hall", "curios": [{"chest": 10}]},
+                                                 {"name": "Lair", "curios": [], "wumpus": true}],
+                                       "halls": [[0, 1], [1, 2]]}"#).unwrap();
+        let stats = simulate(&level, 20, 9, GreedyExplorer::new).unwrap();
+        assert_eq!(stats.wins, 20);
+        assert_eq!(stats.avg_gold(), 10.0);
+        assert_eq!(simulate(&level, 20, 9, GreedyExplorer::new).unwrap(), stats);
+    }
+}
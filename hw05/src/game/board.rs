@@ -1,28 +1,76 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
 use std::result;
 use std::io::Read;
 
-use rustc_serialize::json::Json;
+use rand::{self, SeedableRng, StdRng};
+use rustc_serialize::json::{Json, ToJson};
 
-use super::curio::Curio;
+use super::curio::{Curio, CurioTable};
+use super::event::Sense;
 use super::hall::Hall;
-use super::room::Room;
+use super::monster::Monster;
+use super::room::{Room, RoomId};
+use super::trigger::{Action, Trigger};
 
 pub type Result<T> = result::Result<T, String>;
 
+/// An RNG seeded from a `u64`, so the same seed always gives the same rolls.
+pub fn seeded_rng(seed: u64) -> StdRng {
+    SeedableRng::from_seed(&[seed as usize][..])
+}
+
+/// The board's source of randomness. Everything random that happens during
+/// a game is drawn from it, so a game can be replayed from its seed.
+#[derive(Clone)]
+struct Dice {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl fmt::Debug for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dice {{ seed: {} }}", self.seed)
+    }
+}
+
+/// The castle: an arena of rooms, addressed by `RoomId`, and the halls
+/// between them.
+#[derive(Clone, Debug)]
 pub struct Board {
-    pub rooms: Vec<Rc<RefCell<Room>>>,
+    rooms: Vec<Room>,
+    halls: Vec<Hall>,
+    dice: Dice,
 }
 
 impl Board {
-    pub fn build_board(reader: &mut Read) -> Result<Board> {
-        let mut board = Board { rooms: Vec::new(), };
+    /// An empty board with a random seed.
+    pub fn new() -> Board {
+        Board::with_seed(rand::random())
+    }
 
-        let board_json: Json = try!(Json::from_reader(reader).map_err(|_| "Unable to create JSON reader".to_string()));
+    pub fn with_seed(seed: u64) -> Board {
+        Board { rooms: Vec::new(), halls: Vec::new(), dice: Dice { seed, rng: seeded_rng(seed) } }
+    }
+
+    pub fn build_board(reader: &mut dyn Read) -> Result<Board> {
+        let board_json: Json = Json::from_reader(reader).map_err(|_| "Unable to create JSON reader".to_string())?;
+        Board::from_json(&board_json)
+    }
 
-        try!(board.parse_rooms(&board_json).map_err(|_| "Unable to parse rooms".to_string()));
-        try!(board.parse_halls(&board_json).map_err(|_| "Unable to parse halls".to_string()));
+    /// Build a board from a parsed level with a random seed.
+    pub fn from_json(board_json: &Json) -> Result<Board> {
+        Board::from_json_seeded(board_json, rand::random())
+    }
+
+    /// Build a board from a parsed level. Besides the level format, this also
+    /// accepts the per-room state written by `Board::to_json`. Random curios
+    /// and everything random later in the game are drawn from `seed`.
+    pub fn from_json_seeded(board_json: &Json, seed: u64) -> Result<Board> {
+        let mut board = Board::with_seed(seed);
+
+        board.parse_rooms(board_json).map_err(|e| format!("Unable to parse rooms: {}", e))?;
+        board.parse_halls(board_json).map_err(|e| format!("Unable to parse halls: {}", e))?;
 
         Ok(board)
     }
@@ -30,34 +78,94 @@ impl Board {
     fn parse_rooms(&mut self, json: &Json) -> Result<()> {
         // Find room list
         let rooms_opt: Option<&Json> = json.find("rooms");
-        let json_rooms: &Json = try!(rooms_opt.ok_or("Unable to parse rooms".to_string()));
+        let json_rooms: &Json = rooms_opt.ok_or("Unable to parse rooms".to_string())?;
 
         // Parse each room
-        let rooms: &Vec<Json> = try!(json_rooms.as_array().ok_or("Unable to parse name".to_string()));
+        let rooms: &Vec<Json> = json_rooms.as_array().ok_or("Unable to parse name".to_string())?;
+        // Optional odds and value ranges for random curios
+        let table = match json.find("curio_weights") {
+            Some(json_weights) => CurioTable::from_json(json_weights)?,
+            None => CurioTable::default(),
+        };
+        // Optional number of visits after which looted rooms restock
+        let respawn: Option<u32> = match json.find("respawn") {
+            Some(json_respawn) => Some(json_respawn.as_u64().ok_or("Unable to parse respawn".to_string())? as u32),
+            None => None,
+        };
+
         for r in rooms {
 
             // Parse room name
             let name_opt: Option<&Json> = r.find("name");
-            let json_name: &Json = try!(name_opt.ok_or("Unable to parse name".to_string()));
-            let name: &str = try!(json_name.as_string().ok_or("Unable to parse name".to_string()));
+            let json_name: &Json = name_opt.ok_or("Unable to parse name".to_string())?;
+            let name: &str = json_name.as_string().ok_or("Unable to parse name".to_string())?;
 
-            // Parse curios
+            // Parse curios: either a count of random curios or a list of explicit ones
             let curios_opt: Option<&Json> = r.find("curios");
-            let json_curios: &Json = try!(curios_opt.ok_or("Unable to parse curio".to_string()));
-            let n: u64 = try!(json_curios.as_u64().ok_or("Unable to parse curio".to_string()));
-            let curios: Vec<Curio> = Curio::generate_n(n as usize);
-
-            // Wumpus?
-            let wumpus: bool = {
-                if let Some(json_wumpus) = r.find("wumpus") {
-                    try!(json_wumpus.as_boolean().ok_or("Unable to parse Wumpus".to_string()))
-                } else {
-                    false
-                }
-            };
+            let json_curios: &Json = curios_opt.ok_or("Unable to parse curio".to_string())?;
+            let curios: Vec<Curio> = parse_curios(json_curios, &table, self.rng())?;
+
+            // Wumpus and hazards?
+            let wumpus: bool = parse_flag(r, "wumpus")?;
+            let pit: bool = parse_flag(r, "pit")?;
+            let bats: bool = parse_flag(r, "bats")?;
 
             // Add the new room to self.rooms
-	    self.rooms.push(Rc::new(RefCell::new(Room::new(name, curios, wumpus))));
+            let mut room = Room::new(name, curios, wumpus);
+            room.respawn = respawn;
+            room.pit = pit;
+            room.bats = bats;
+            room.description = parse_text(r, "description")?;
+            room.first_visit = parse_text(r, "first_visit")?;
+            if let Some(json_monsters) = r.find("monsters") {
+                let list = json_monsters.as_array().ok_or("Unable to parse monsters".to_string())?;
+                for m in list {
+                    room.monsters.push(Monster::from_json(m, &table, self.rng())?);
+                }
+            }
+            if let Some(json_triggers) = r.find("triggers") {
+                let list = json_triggers.as_array().ok_or("Unable to parse triggers".to_string())?;
+                for t in list {
+                    let source = t.as_string().ok_or("Unable to parse triggers".to_string())?;
+                    room.triggers.push(Trigger::parse(source)?);
+                }
+            }
+
+            // Saved room state
+            if let Some(json_respawn) = r.find("respawn") {
+                room.respawn = Some(json_respawn.as_u64().ok_or("Unable to parse respawn".to_string())? as u32);
+            }
+            if let Some(json_visits) = r.find("visits") {
+                room.visits = json_visits.as_u64().ok_or("Unable to parse visits".to_string())? as u32;
+            }
+            if let Some(json_looted) = r.find("looted_at") {
+                room.looted_at = json_looted.as_u64().ok_or("Unable to parse looted_at".to_string())? as u32;
+            }
+            if let Some(json_spawn) = r.find("spawn") {
+                room.spawn = parse_curio_list(json_spawn, &table, self.rng())?;
+            }
+            if let Some(json_fired) = r.find("fired") {
+                let list = json_fired.as_array().ok_or("Unable to parse fired".to_string())?;
+                for i in list {
+                    let i = i.as_u64().ok_or("Unable to parse fired".to_string())? as usize;
+                    let trigger = room.triggers.get_mut(i).ok_or("Invalid fired trigger".to_string())?;
+                    trigger.fired = true;
+                }
+            }
+            self.add_room(room);
+        }
+
+        // Triggers may only open halls to rooms that exist.
+        for room in &self.rooms {
+            for t in &room.triggers {
+                for a in &t.actions {
+                    if let Action::OpenHall(ref name) = *a {
+                        if self.room_named(name).is_none() {
+                            return Err(format!("Trigger in {} opens a hall to unknown room {}", room.name, name));
+                        }
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -65,32 +173,403 @@ impl Board {
     fn parse_halls(&mut self, json: &Json) -> Result<()> {
         // Find hall list
         let halls_opt: Option<&Json> = json.find("halls");
-        let json_halls: &Json = try!(halls_opt.ok_or("Unable to parse halls".to_string()));
+        let json_halls: &Json = halls_opt.ok_or("Unable to parse halls".to_string())?;
 
         // Parse each hall
-        let halls: &Vec<Json> = try!(json_halls.as_array().ok_or("Unable to parse halls".to_string()));
+        let halls: &Vec<Json> = json_halls.as_array().ok_or("Unable to parse halls".to_string())?;
         for h in halls {
-            let h: &Vec<Json> = try!(h.as_array().ok_or("Unable to parse halls".to_string()));
-            if h.len() > 2 { return Err("Invalid number of rooms per hall".to_string()); }
-	    let r1_idx: usize = try!(h[0].as_u64().ok_or("Invalid room index".to_string())) as usize;
-	    let r2_idx: usize = try!(h[1].as_u64().ok_or("Invalid room index".to_string())) as usize;
+            let h: &Vec<Json> = h.as_array().ok_or("Unable to parse halls".to_string())?;
+            if h.len() != 2 { return Err("Invalid number of rooms per hall".to_string()); }
+            let r1_idx: usize = h[0].as_u64().ok_or("Invalid room index".to_string())? as usize;
+            let r2_idx: usize = h[1].as_u64().ok_or("Invalid room index".to_string())? as usize;
             if r1_idx >= self.rooms.len() ||
-	       r2_idx >= self.rooms.len() {
-		   return Err("Invalid room numbers per hall".to_string());
-	    }
+               r2_idx >= self.rooms.len() {
+                return Err("Invalid room numbers per hall".to_string());
+            }
 
-            let hall = Hall::new(self.rooms[r1_idx].clone(), self.rooms[r2_idx].clone());
-
-            // Add hall links to rooms
-	    let hall_rc = Rc::new(hall);
-	    self.rooms[r1_idx].borrow_mut().add_hall(hall_rc.clone());
-	    self.rooms[r2_idx].borrow_mut().add_hall(hall_rc.clone());
+            self.add_hall(RoomId(r1_idx), RoomId(r2_idx));
         }
         Ok(())
     }
 
-    pub fn spawn_location(&self) -> Rc<RefCell<Room>> {
-        self.rooms[0].clone()
+    /// Add a room with no halls to the board.
+    pub fn add_room(&mut self, room: Room) -> RoomId {
+        self.rooms.push(room);
+        RoomId(self.rooms.len() - 1)
+    }
+
+    /// Connect two rooms of the board with a hall.
+    pub fn add_hall(&mut self, left: RoomId, right: RoomId) {
+        self.halls.push(Hall::new(left, right));
+        self.rooms[left.0].add_exit(right);
+        self.rooms[right.0].add_exit(left);
+    }
+
+    /// The seed the board's RNG started from.
+    pub fn seed(&self) -> u64 {
+        self.dice.seed
+    }
+
+    /// The board's RNG. Draw everything random in the game from here.
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.dice.rng
+    }
+
+    pub fn spawn_location(&self) -> RoomId {
+        RoomId(0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rooms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rooms.is_empty()
+    }
+
+    /// The ids of every room on the board.
+    pub fn room_ids(&self) -> Vec<RoomId> {
+        (0..self.rooms.len()).map(RoomId).collect()
+    }
+
+    pub fn room(&self, id: RoomId) -> &Room {
+        &self.rooms[id.0]
+    }
+
+    pub fn room_mut(&mut self, id: RoomId) -> &mut Room {
+        &mut self.rooms[id.0]
+    }
+
+    pub fn halls(&self) -> &[Hall] {
+        &self.halls
+    }
+
+    /// The rooms at the other end of each of `id`'s halls.
+    pub fn neighbors(&self, id: RoomId) -> &[RoomId] {
+        self.rooms[id.0].exits()
+    }
+
+    /// Find a neighbor of `id` by name. Case insensitive.
+    pub fn find_neighbor(&self, id: RoomId, name: &str) -> Option<RoomId> {
+        self.neighbors(id).iter()
+            .cloned()
+            .find(|&n| self.room(n).name.to_lowercase() == name.to_lowercase())
+    }
+
+    /// Find any room on the board by name. Case insensitive.
+    pub fn room_named(&self, name: &str) -> Option<RoomId> {
+        self.room_ids().into_iter().find(|&r| self.room(r).name.to_lowercase() == name.to_lowercase())
+    }
+
+    /// The shortest path from `from` to the nearest room that is a `goal`,
+    /// entering only rooms that are `passable`, found by breadth-first
+    /// search. The path lists the rooms to walk into in order, ending at the
+    /// goal, and is empty if `from` is a goal itself.
+    pub fn find_path<P, G>(&self, from: RoomId, passable: P, goal: G) -> Option<Vec<RoomId>>
+        where P: Fn(RoomId) -> bool, G: Fn(RoomId) -> bool {
+        if goal(from) {
+            return Some(Vec::new());
+        }
+        let mut came_from = vec![None; self.len()];
+        let mut queue = VecDeque::new();
+        came_from[from.0] = Some(from);
+        queue.push_back(from);
+        while let Some(room) = queue.pop_front() {
+            for &n in self.neighbors(room) {
+                if came_from[n.0].is_some() || !passable(n) {
+                    continue;
+                }
+                came_from[n.0] = Some(room);
+                if goal(n) {
+                    let mut path = vec![n];
+                    let mut cur = room;
+                    while cur != from {
+                        path.push(cur);
+                        cur = came_from[cur.0].unwrap();
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                queue.push_back(n);
+            }
+        }
+        None
+    }
+
+    /// The room the wumpus lurks in, if it is still alive.
+    pub fn wumpus_location(&self) -> Option<RoomId> {
+        self.room_ids().into_iter().find(|&id| self.room(id).wumpus)
+    }
+
+    /// Warnings about the hazards lurking in the rooms next to `id`.
+    pub fn senses(&self, id: RoomId) -> Vec<Sense> {
+        let neighbors = self.neighbors(id);
+        let mut senses = Vec::new();
+        if neighbors.iter().any(|&n| self.room(n).wumpus) {
+            senses.push(Sense::Wumpus);
+        }
+        if neighbors.iter().any(|&n| self.room(n).pit) {
+            senses.push(Sense::Draft);
+        }
+        if neighbors.iter().any(|&n| self.room(n).bats) {
+            senses.push(Sense::Flapping);
+        }
+        senses
+    }
+
+    pub fn neighbors_string(&self, id: RoomId) -> String {
+        let names: Vec<&str> = self.neighbors(id).iter().map(|&n| &self.room(n).name[..]).collect();
+        names.join(" ")
+    }
+
+    /// A Graphviz graph of the rooms and halls. Rooms are labelled with their
+    /// curio counts and hazards, and the wumpus's lair is filled in red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph castle {\n");
+        for id in self.room_ids() {
+            let room = self.room(id);
+            let mut label = format!("{}\\n{} curios", room.name.replace('\\', "\\\\").replace('"', "\\\""), room.contents.len());
+            let mut attrs = String::new();
+            if room.wumpus {
+                label.push_str("\\nwumpus");
+                attrs.push_str(", style=filled, fillcolor=red");
+            }
+            if room.pit {
+                label.push_str("\\npit");
+            }
+            if room.bats {
+                label.push_str("\\nbats");
+            }
+            dot.push_str(&format!("    r{} [label=\"{}\"{}];\n", id, label, attrs));
+        }
+        for h in &self.halls {
+            dot.push_str(&format!("    r{} -- r{};\n", h.left, h.right));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// An adjacency listing of the rooms in `visited`, the rooms one player
+    /// has been to, plus `here`. Exits to unvisited rooms are shown but not
+    /// explored.
+    pub fn to_ascii(&self, here: RoomId, visited: &[RoomId]) -> String {
+        let visited = |r: RoomId| r == here || visited.contains(&r);
+        let mut map = String::new();
+        for id in self.room_ids().into_iter().filter(|&r| visited(r)) {
+            let marker = if id == here { "*" } else { " " };
+            map.push_str(&format!("{} {}\n", marker, self.room(id).name));
+            for &n in self.neighbors(id) {
+                let fog = if visited(n) { String::new() } else { format!(" {}", t!("map.unexplored")) };
+                map.push_str(&format!("    +-- {}{}\n", self.room(n).name, fog));
+            }
+        }
+        map
+    }
+
+    /// Describe the board, including the current state of every room, in the
+    /// level format read by `Board::from_json`.
+    pub fn to_json(&self) -> Json {
+        let mut rooms = Vec::new();
+        for room in &self.rooms {
+            let mut obj = BTreeMap::new();
+            obj.insert("name".to_string(), room.name.to_json());
+            if let Some(ref text) = room.description {
+                obj.insert("description".to_string(), text.to_json());
+            }
+            if let Some(ref text) = room.first_visit {
+                obj.insert("first_visit".to_string(), text.to_json());
+            }
+            if !room.triggers.is_empty() {
+                let sources: Vec<Json> = room.triggers.iter().map(|t| t.source.to_json()).collect();
+                obj.insert("triggers".to_string(), Json::Array(sources));
+                let fired: Vec<usize> = (0..room.triggers.len()).filter(|&i| room.triggers[i].fired).collect();
+                if !fired.is_empty() {
+                    obj.insert("fired".to_string(), fired.to_json());
+                }
+            }
+            obj.insert("curios".to_string(), room.contents.to_json());
+            if !room.monsters.is_empty() {
+                obj.insert("monsters".to_string(), room.monsters.to_json());
+            }
+            for &(key, flag) in &[("wumpus", room.wumpus), ("pit", room.pit), ("bats", room.bats)] {
+                if flag {
+                    obj.insert(key.to_string(), flag.to_json());
+                }
+            }
+            if room.visits > 0 {
+                obj.insert("visits".to_string(), room.visits.to_json());
+            }
+            if let Some(respawn) = room.respawn {
+                obj.insert("respawn".to_string(), respawn.to_json());
+                obj.insert("spawn".to_string(), room.spawn.to_json());
+                obj.insert("looted_at".to_string(), room.looted_at.to_json());
+            }
+            rooms.push(Json::Object(obj));
+        }
+        let halls: Vec<Json> = self.halls.iter().map(|h| vec![h.left.0, h.right.0].to_json()).collect();
+
+        let mut obj = BTreeMap::new();
+        obj.insert("rooms".to_string(), Json::Array(rooms));
+        obj.insert("halls".to_string(), Json::Array(halls));
+        Json::Object(obj)
+    }
+}
+
+impl Default for Board {
+    fn default() -> Board {
+        Board::new()
+    }
+}
+
+/// Parse either a count of random curios or a list of explicit ones.
+pub fn parse_curios(json: &Json, table: &CurioTable, rng: &mut StdRng) -> Result<Vec<Curio>> {
+    if json.is_array() {
+        parse_curio_list(json, table, rng)
+    } else {
+        let n: u64 = json.as_u64().ok_or("Unable to parse curio".to_string())?;
+        Ok(table.generate_n(rng, n as usize))
     }
 }
 
+/// Parse a list of explicit curios.
+fn parse_curio_list(json: &Json, table: &CurioTable, rng: &mut StdRng) -> Result<Vec<Curio>> {
+    let list = json.as_array().ok_or("Unable to parse curio".to_string())?;
+    let mut curios = Vec::new();
+    for c in list {
+        curios.push(Curio::from_json(c, table, rng)?);
+    }
+    Ok(curios)
+}
+
+/// Parse an optional text field of a room.
+fn parse_text(room: &Json, key: &str) -> Result<Option<String>> {
+    match room.find(key) {
+        Some(json_text) => json_text.as_string().map(|t| Some(t.to_string())).ok_or(format!("Unable to parse {}", key)),
+        None => Ok(None),
+    }
+}
+
+/// Parse an optional boolean field of a room, defaulting to false.
+fn parse_flag(room: &Json, key: &str) -> Result<bool> {
+    match room.find(key) {
+        Some(json_flag) => json_flag.as_boolean().ok_or(format!("Unable to parse {}", key)),
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Board;
+    use super::super::curio::{Chest, Curio};
+    use super::super::event::{Event, Sense};
+    use super::super::difficulty::Difficulty;
+    use super::super::player::{Command, Player};
+    use super::super::room::{Room, RoomId};
+
+    #[test]
+    fn neighbors_and_senses() {
+        let mut board = Board::new();
+        let here = board.add_room(Room::new("Here", Vec::new(), false));
+        let lair = board.add_room(Room::new("Lair", Vec::new(), true));
+        let pit = board.add_room(Room::new("Pit Room", Vec::new(), false));
+        board.room_mut(pit).pit = true;
+        board.add_hall(here, lair);
+        board.add_hall(here, pit);
+
+        assert_eq!(board.neighbors(here), &[lair, pit]);
+        assert_eq!(board.neighbors(lair), &[here]);
+        assert_eq!(board.find_neighbor(here, "pit room"), Some(pit));
+        assert_eq!(board.find_neighbor(lair, "pit room"), None);
+        assert_eq!(board.wumpus_location(), Some(lair));
+        assert_eq!(board.senses(here), vec![Sense::Wumpus, Sense::Draft]);
+        assert!(board.senses(lair).is_empty());
+        assert_eq!(board.halls()[0].other(here), Some(lair));
+        assert_eq!(board.halls()[0].other(pit), None);
+        assert_eq!(board.spawn_location(), RoomId(0));
+    }
+
+    #[test]
+    fn dot_and_ascii() {
+        let mut board = Board::new();
+        let entry = board.add_room(Room::new("Entry", Vec::new(), false));
+        let hall = board.add_room(Room::new("Hall", vec![Curio::new(Chest(3))], false));
+        let lair = board.add_room(Room::new("Lair", Vec::new(), true));
+        board.add_hall(entry, hall);
+        board.add_hall(hall, lair);
+
+        assert_eq!(board.to_dot(), "graph castle {\n\
+                                    \x20   r0 [label=\"Entry\\n0 curios\"];\n\
+                                    \x20   r1 [label=\"Hall\\n1 curios\"];\n\
+                                    \x20   r2 [label=\"Lair\\n0 curios\\nwumpus\", style=filled, fillcolor=red];\n\
+                                    \x20   r0 -- r1;\n\
+                                    \x20   r1 -- r2;\n\
+                                    }\n");
+
+        board.room_mut(hall).name = r#"The "C:\" Drive"#.to_string();
+        assert!(board.to_dot().contains(r#"r1 [label="The \"C:\\\" Drive\n1 curios"];"#));
+        board.room_mut(hall).name = "Hall".to_string();
+
+        // Another player's visit to Lair does not clear the fog.
+        board.room_mut(lair).visits = 1;
+        assert_eq!(board.to_ascii(hall, &[entry]), "  Entry\n    +-- Hall\n\
+                                          * Hall\n    +-- Entry\n    +-- Lair (unexplored)\n");
+    }
+
+    #[test]
+    fn paths() {
+        let mut board = Board::new();
+        let rooms: Vec<RoomId> = (0..5).map(|i| board.add_room(Room::new(&i.to_string(), Vec::new(), false))).collect();
+        // 0 - 1 - 2 - 3, with a shortcut 0 - 4 - 3.
+        board.add_hall(rooms[0], rooms[1]);
+        board.add_hall(rooms[1], rooms[2]);
+        board.add_hall(rooms[2], rooms[3]);
+        board.add_hall(rooms[0], rooms[4]);
+        board.add_hall(rooms[4], rooms[3]);
+
+        let to = |goal: RoomId| move |r: RoomId| r == goal;
+        assert_eq!(board.find_path(rooms[0], |_| true, to(rooms[3])), Some(vec![rooms[4], rooms[3]]));
+        assert_eq!(board.find_path(rooms[0], |r| r != rooms[4], to(rooms[3])),
+                   Some(vec![rooms[1], rooms[2], rooms[3]]));
+        assert_eq!(board.find_path(rooms[0], |r| r == rooms[1], to(rooms[3])), None);
+        assert_eq!(board.find_path(rooms[2], |_| true, to(rooms[2])), Some(Vec::new()));
+        assert_eq!(board.find_path(rooms[1], |_| true, |r| r == rooms[3] || r == rooms[4]), Some(vec![rooms[0], rooms[4]]));
+    }
+
+    #[test]
+    fn rejects_bad_halls() {
+        let level = r#"{"rooms": [{"name": "A", "curios": 0}], "halls": [[0, 1]]}"#;
+        assert!(Board::build_board(&mut level.as_bytes()).is_err());
+        let level = r#"{"rooms": [{"name": "A", "curios": 0}], "halls": [[0]]}"#;
+        assert!(Board::build_board(&mut level.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn story_rooms() {
+        let level = r#"{"rooms": [{"name": "Gate", "curios": 0, "description": "A rusted portcullis.",
+                                   "triggers": ["on enter: if has key, take key, open hall to Treasure",
+                                                "on enter: damage 3 once"]},
+                                  {"name": "Shed", "curios": [{"key": null}], "first_visit": "Cobwebs everywhere."},
+                                  {"name": "Treasure", "curios": [{"chest": 40}]}],
+                        "halls": [[0, 1]]}"#;
+        let mut board = Board::build_board(&mut level.as_bytes()).unwrap();
+        let (mut player, events) = Player::spawn(&mut board, Difficulty::default());
+        assert_eq!(events, vec![Event::Damage(3)]);
+        assert_eq!(board.room(RoomId(0)).description, Some("A rusted portcullis.".to_string()));
+
+        let events = player.act(&mut board, Command::Go("shed".to_string())).unwrap();
+        assert_eq!(events, vec![Event::Message("Cobwebs everywhere.".to_string()), Event::Key]);
+        let events = player.act(&mut board, Command::Go("gate".to_string())).unwrap();
+        assert_eq!(events, vec![Event::ItemLost("key".to_string()), Event::HallOpened("Treasure".to_string())]);
+        assert_eq!(player.hp, 22);
+        assert_eq!(board.neighbors(RoomId(0)), &[RoomId(1), RoomId(2)]);
+
+        // Fired triggers and opened halls survive a save.
+        let saved = Board::from_json(&board.to_json()).unwrap();
+        assert_eq!(saved.to_json(), board.to_json());
+        assert!(saved.room(RoomId(0)).triggers[1].fired);
+        assert_eq!(saved.neighbors(RoomId(2)), &[RoomId(0)]);
+
+        let bad = r#"{"rooms": [{"name": "A", "curios": 0, "triggers": ["on enter: open hall to Nowhere"]}], "halls": []}"#;
+        assert!(Board::build_board(&mut bad.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use rustc_serialize::json::Json;
+
+use super::board::Result;
+use super::difficulty::Difficulty;
+
+/// Several levels played one after another. Each level must be won to go on
+/// to the next, and the player's gold and HP carry over between them.
+///
+/// A campaign manifest looks like
+/// `{"name": "The Long Night", "difficulty": "hard", "levels": ["castle.json", "crypt.json"]}`,
+/// where the difficulty is optional and level paths are relative to the
+/// manifest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Campaign {
+    pub name: String,
+    pub difficulty: Option<Difficulty>,
+    pub levels: Vec<PathBuf>,
+}
+
+impl Campaign {
+    /// Parse a manifest kept in the directory `dir`.
+    pub fn from_json(json: &Json, dir: &Path) -> Result<Campaign> {
+        let name = json.find("name").and_then(|n| n.as_string()).ok_or("Unable to parse name".to_string())?;
+        let difficulty = match json.find("difficulty") {
+            Some(d) => Some(d.as_string().and_then(Difficulty::from_name).ok_or("Unable to parse difficulty".to_string())?),
+            None => None,
+        };
+        let list = json.find("levels").and_then(|l| l.as_array()).ok_or("Unable to parse levels".to_string())?;
+        if list.is_empty() {
+            return Err("A campaign needs at least one level".to_string());
+        }
+        let mut levels = Vec::new();
+        for l in list {
+            let path = l.as_string().ok_or("Unable to parse levels".to_string())?;
+            levels.push(dir.join(path));
+        }
+        Ok(Campaign { name: name.to_string(), difficulty, levels })
+    }
+
+    pub fn load(path: &Path) -> Result<Campaign> {
+        let mut file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+        let json = Json::from_reader(&mut file).map_err(|_| "Unable to create JSON reader".to_string())?;
+        Campaign::from_json(&json, path.parent().unwrap_or_else(|| Path::new("")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use rustc_serialize::json::Json;
+
+    use super::Campaign;
+    use super::super::board::Board;
+    use super::super::difficulty::HARD;
+    use super::super::player::{Command, Player};
+    use super::super::room::RoomId;
+
+    #[test]
+    fn parse_manifest() {
+        let json = Json::from_str(r#"{"name": "Night", "difficulty": "hard", "levels": ["a.json", "b/c.json"]}"#).unwrap();
+        let campaign = Campaign::from_json(&json, Path::new("data")).unwrap();
+        assert_eq!(campaign.name, "Night");
+        assert_eq!(campaign.difficulty, Some(HARD));
+        assert_eq!(campaign.levels, vec![PathBuf::from("data/a.json"), PathBuf::from("data/b/c.json")]);
+
+        let json = Json::from_str(r#"{"name": "Night", "levels": []}"#).unwrap();
+        assert!(Campaign::from_json(&json, Path::new("")).is_err());
+        let json = Json::from_str(r#"{"name": "Night", "difficulty": "brutal", "levels": ["a.json"]}"#).unwrap();
+        assert!(Campaign::from_json(&json, Path::new("")).is_err());
+    }
+
+    #[test]
+    fn gold_and_hp_carry_over() {
+        let level = r#"{"rooms": [{"name": "Entry", "curios": [{"chest": 5}]},
+                                  {"name": "Lair", "curios": 0, "wumpus": true}],
+                        "halls": [[0, 1]]}"#;
+        let mut board = Board::build_board(&mut level.as_bytes()).unwrap();
+        let (mut player, _) = Player::spawn(&mut board, HARD);
+        player.hp = 4;
+        player.act(&mut board, Command::Shoot("lair".to_string())).unwrap();
+        assert!(player.won);
+
+        let mut board = Board::build_board(&mut level.as_bytes()).unwrap();
+        let (next, _) = player.next_level(&mut board);
+        assert_eq!((next.gold, next.hp, next.arrows), (10, 4, HARD.arrows));
+        assert_eq!(next.location, RoomId(0));
+        assert!(!next.won);
+        assert_eq!(next.stats.turns, 0);
+    }
+}
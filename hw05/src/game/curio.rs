@@ -1,58 +1,594 @@
-use rand;
+use rand::{Rng, StdRng};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
 
-const MAX_CHEST_VAL: u32 = 100;
-const MAX_TRAP_VAL: u32 = 10;
-const MAX_FOOD_VAL: u32 = 10;
-const NUM_CURIO_VARIANTS: usize = 5;
-const NUM_NONRECURSIVE_CURIOS: usize = 3;
+use rustc_serialize::json::{Json, ToJson};
 
-#[derive(Clone)]
-#[derive(Debug)]
-pub enum Curio {
-    Chest(i32),
-    SpikeTrap(i32),
-    Food(i32),
-    IronMaiden(Box<Curio>, i32),
-    FallenAdventurer(Box<Curio>),
+use super::board::{Board, Result};
+use super::event::Event;
+use super::player::Player;
+use super::room::RoomId;
+
+/// One kind of curio: what it does to the player who finds it and how it is
+/// written in level files. To add a new kind, implement this trait and list
+/// it in `KINDS`.
+pub trait CurioKind: fmt::Debug + Send {
+    /// The key naming this kind in level files, e.g. `"chest"`.
+    fn key(&self) -> &'static str;
+
+    /// The value stored under `key()` in level files.
+    fn value_json(&self) -> Json;
+
+    /// Spring the curio on whoever found it.
+    fn use_on(self: Box<Self>, finder: &mut Finder);
+
+    fn box_clone(&self) -> Box<dyn CurioKind>;
 }
 
+/// Something lying in a room, waiting to be found.
+#[derive(Debug)]
+pub struct Curio(Box<dyn CurioKind>);
+
 impl Curio {
-    pub fn generate_n(n: usize) -> Vec<Curio> {
-        let mut acc = Vec::new();
-        for _ in 0..n { acc.push(Curio::generate()); }
-        acc
+    pub fn new<K: CurioKind + 'static>(kind: K) -> Curio {
+        Curio(Box::new(kind))
+    }
+
+    pub fn key(&self) -> &'static str {
+        self.0.key()
     }
 
-    pub fn generate() -> Curio {
-        match rand::random::<usize>() % NUM_CURIO_VARIANTS {
-            0 => Curio::rand_chest(),
-            1 => Curio::rand_spike_trap(),
-            2 => Curio::rand_food(),
-            3 => Curio::IronMaiden(Box::new(Curio::generate_sub_curio()),
-                                   ((rand::random::<u32>() % MAX_TRAP_VAL) + 1) as i32),
-            4 => Curio::FallenAdventurer(Box::new(Curio::generate_sub_curio())),
-            _ => unreachable!(),
+    /// Build a curio from its level-file description. Each curio is a
+    /// single-key object, e.g. `{"chest": 50}` or
+    /// `{"iron_maiden": {"dmg": 4, "inner": {"chest": 50}}}`. The string
+    /// `"random"` draws a random curio from `table`, as does an iron maiden
+    /// or fallen adventurer without an inner curio, using `rng`.
+    pub fn from_json(json: &Json, table: &CurioTable, rng: &mut StdRng) -> Result<Curio> {
+        if json.as_string() == Some("random") {
+            return Ok(table.generate(rng));
         }
+        let obj = json.as_object().ok_or("Unable to parse curio".to_string())?;
+        if obj.len() != 1 {
+            return Err("A curio must have exactly one kind".to_string());
+        }
+        let (key, val) = obj.iter().next().unwrap();
+        let kind = find_kind(key).ok_or(format!("Unknown curio {}", key))?;
+        (kind.parse)(val, table, rng)
+    }
+}
+
+impl Clone for Curio {
+    fn clone(&self) -> Curio {
+        Curio(self.0.box_clone())
+    }
+}
+
+impl PartialEq for Curio {
+    fn eq(&self, other: &Curio) -> bool {
+        self.to_json() == other.to_json()
+    }
+}
+
+impl ToJson for Curio {
+    /// The level-file description of the curio, as read by `Curio::from_json`.
+    fn to_json(&self) -> Json {
+        let mut obj = BTreeMap::new();
+        obj.insert(self.key().to_string(), self.0.value_json());
+        Json::Object(obj)
+    }
+}
+
+/// What a curio can get at when the player finds it.
+pub struct Finder<'a> {
+    pub player: &'a mut Player,
+    pub board: &'a mut Board,
+    pub events: &'a mut Vec<Event>,
+    /// Curios to put back in the room rather than use up.
+    pub left_behind: Vec<Curio>,
+    /// A room to send the player to once the room's curios are used.
+    pub teleport: Option<RoomId>,
+}
+
+impl<'a> Finder<'a> {
+    pub fn new(player: &'a mut Player, board: &'a mut Board, events: &'a mut Vec<Event>) -> Finder<'a> {
+        Finder { player, board, events, left_behind: Vec::new(), teleport: None }
+    }
+
+    pub fn use_curio(&mut self, curio: Curio) {
+        curio.0.use_on(self);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Chest(pub i32);
+
+impl CurioKind for Chest {
+    fn key(&self) -> &'static str { "chest" }
+    fn value_json(&self) -> Json { self.0.to_json() }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        finder.events.push(Event::Chest(self.0));
+        finder.player.gold += self.0;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpikeTrap(pub i32);
+
+impl CurioKind for SpikeTrap {
+    fn key(&self) -> &'static str { "spike_trap" }
+    fn value_json(&self) -> Json { self.0.to_json() }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        let dmg = finder.player.difficulty.trap(self.0);
+        finder.events.push(Event::SpikeTrap(dmg));
+        finder.player.hurt(dmg);
     }
+}
+
+#[derive(Clone, Debug)]
+pub struct Food(pub i32);
 
-    fn rand_chest() -> Curio {
-        Curio::Chest(((rand::random::<u32>() % MAX_CHEST_VAL) + 1) as i32)
+impl CurioKind for Food {
+    fn key(&self) -> &'static str { "food" }
+    fn value_json(&self) -> Json { self.0.to_json() }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        let heal = finder.player.difficulty.food(self.0);
+        finder.events.push(Event::Food(heal));
+        finder.player.stats.food_eaten += 1;
+        finder.player.heal(heal);
     }
+}
+
+#[derive(Clone, Debug)]
+pub struct IronMaiden {
+    pub dmg: i32,
+    pub inner: Curio,
+}
 
-    fn rand_spike_trap() -> Curio {
-        Curio::SpikeTrap(((rand::random::<u32>() % MAX_TRAP_VAL) + 1) as i32)
+impl CurioKind for IronMaiden {
+    fn key(&self) -> &'static str { "iron_maiden" }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn value_json(&self) -> Json {
+        let mut obj = BTreeMap::new();
+        obj.insert("dmg".to_string(), self.dmg.to_json());
+        obj.insert("inner".to_string(), self.inner.to_json());
+        Json::Object(obj)
+    }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        let dmg = finder.player.difficulty.trap(self.dmg);
+        finder.events.push(Event::IronMaiden(dmg));
+        finder.player.hurt(dmg);
+        finder.use_curio(self.inner);
     }
+}
 
-    fn rand_food() -> Curio {
-        Curio::Food(((rand::random::<u32>() % MAX_FOOD_VAL) + 1) as i32)
+#[derive(Clone, Debug)]
+pub struct FallenAdventurer(pub Curio);
+
+impl CurioKind for FallenAdventurer {
+    fn key(&self) -> &'static str { "fallen_adventurer" }
+    fn value_json(&self) -> Json { self.0.to_json() }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        finder.events.push(Event::FallenAdventurer);
+        finder.use_curio(self.0);
     }
+}
+
+/// Raises the player's maximum HP, and their HP along with it.
+#[derive(Clone, Debug)]
+pub struct Potion(pub i32);
 
-    fn generate_sub_curio() -> Curio {
-        match rand::random::<usize>() % NUM_NONRECURSIVE_CURIOS {
-            0 => Curio::rand_chest(),
-            1 => Curio::rand_spike_trap(),
-            2 => Curio::rand_food(),
-            _ => unreachable!(),
+impl CurioKind for Potion {
+    fn key(&self) -> &'static str { "potion" }
+    fn value_json(&self) -> Json { self.0.to_json() }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        finder.events.push(Event::Potion(self.0));
+        finder.player.max_hp += self.0;
+        finder.player.hp += self.0;
+    }
+}
+
+/// Opens one locked chest.
+#[derive(Clone, Debug)]
+pub struct Key;
+
+impl CurioKind for Key {
+    fn key(&self) -> &'static str { "key" }
+    fn value_json(&self) -> Json { Json::Null }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        finder.events.push(Event::Key);
+        finder.player.add_item("key");
+    }
+}
+
+/// A chest that takes a key to open. Without one it stays where it is.
+#[derive(Clone, Debug)]
+pub struct LockedChest(pub i32);
+
+impl CurioKind for LockedChest {
+    fn key(&self) -> &'static str { "locked_chest" }
+    fn value_json(&self) -> Json { self.0.to_json() }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        if finder.player.take_item("key") {
+            finder.events.push(Event::LockedChest(self.0));
+            finder.player.gold += self.0;
+        } else {
+            finder.events.push(Event::ChestLocked);
+            finder.left_behind.push(Curio(self));
         }
     }
 }
+
+/// Sends the player to the named room, or to a random one if the room is
+/// missing or not given.
+#[derive(Clone, Debug)]
+pub struct Teleporter(pub Option<String>);
+
+impl CurioKind for Teleporter {
+    fn key(&self) -> &'static str { "teleporter" }
+    fn value_json(&self) -> Json { self.0.to_json() }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        let board = &mut *finder.board;
+        let here = finder.player.location;
+        let named = self.0.as_ref().and_then(|name| board.room_named(name));
+        let others: Vec<RoomId> = board.room_ids().into_iter().filter(|&r| r != here).collect();
+        let dest = match named.or_else(|| board.rng().choose(&others).cloned()) {
+            Some(dest) => dest,
+            None => return,
+        };
+        finder.events.push(Event::Teleported(board.room(dest).name.clone()));
+        finder.teleport = Some(dest);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Quiver(pub i32);
+
+impl CurioKind for Quiver {
+    fn key(&self) -> &'static str { "quiver" }
+    fn value_json(&self) -> Json { self.0.to_json() }
+    fn box_clone(&self) -> Box<dyn CurioKind> { Box::new(self.clone()) }
+
+    fn use_on(self: Box<Self>, finder: &mut Finder) {
+        finder.events.push(Event::Quiver(self.0));
+        finder.player.arrows += self.0;
+    }
+}
+
+/// How to read and randomly build one kind of curio.
+struct Kind {
+    key: &'static str,
+    /// Whether the curio holds another. Random containers only ever hold
+    /// curios that are not containers themselves.
+    container: bool,
+    parse: fn(&Json, &CurioTable, &mut StdRng) -> Result<Curio>,
+    /// Build a curio worth `val`, holding `inner` if it is a container.
+    build: fn(i32, Option<Curio>) -> Curio,
+    /// The weight and value range used unless the level says otherwise.
+    default: Weight,
+}
+
+static KINDS: &[Kind] = &[
+    Kind {
+        key: "chest",
+        container: false,
+        parse: |val, _, _| Ok(Curio::new(Chest(parse_val(val, "chest")?))),
+        build: |val, _| Curio::new(Chest(val)),
+        default: Weight { weight: 1, min: 1, max: 100 },
+    },
+    Kind {
+        key: "spike_trap",
+        container: false,
+        parse: |val, _, _| Ok(Curio::new(SpikeTrap(parse_val(val, "spike_trap")?))),
+        build: |val, _| Curio::new(SpikeTrap(val)),
+        default: Weight { weight: 1, min: 1, max: 10 },
+    },
+    Kind {
+        key: "food",
+        container: false,
+        parse: |val, _, _| Ok(Curio::new(Food(parse_val(val, "food")?))),
+        build: |val, _| Curio::new(Food(val)),
+        default: Weight { weight: 1, min: 1, max: 10 },
+    },
+    Kind {
+        key: "iron_maiden",
+        container: true,
+        parse: |val, table, rng| {
+            let dmg = val.find("dmg").ok_or("Iron maiden needs dmg".to_string())?;
+            let dmg = parse_val(dmg, "iron_maiden")?;
+            Ok(Curio::new(IronMaiden { dmg, inner: parse_inner(val.find("inner"), table, rng)? }))
+        },
+        build: |val, inner| Curio::new(IronMaiden { dmg: val, inner: inner.unwrap() }),
+        default: Weight { weight: 1, min: 1, max: 10 },
+    },
+    Kind {
+        key: "fallen_adventurer",
+        container: true,
+        parse: |val, table, rng| {
+            let inner = if val.is_null() { None } else { Some(val) };
+            Ok(Curio::new(FallenAdventurer(parse_inner(inner, table, rng)?)))
+        },
+        build: |_, inner| Curio::new(FallenAdventurer(inner.unwrap())),
+        default: Weight { weight: 1, min: 0, max: 0 },
+    },
+    Kind {
+        key: "potion",
+        container: false,
+        parse: |val, _, _| Ok(Curio::new(Potion(parse_val(val, "potion")?))),
+        build: |val, _| Curio::new(Potion(val)),
+        default: Weight { weight: 0, min: 1, max: 5 },
+    },
+    Kind {
+        key: "key",
+        container: false,
+        parse: |_, _, _| Ok(Curio::new(Key)),
+        build: |_, _| Curio::new(Key),
+        default: Weight { weight: 0, min: 0, max: 0 },
+    },
+    Kind {
+        key: "locked_chest",
+        container: false,
+        parse: |val, _, _| Ok(Curio::new(LockedChest(parse_val(val, "locked_chest")?))),
+        build: |val, _| Curio::new(LockedChest(val)),
+        default: Weight { weight: 0, min: 50, max: 200 },
+    },
+    Kind {
+        key: "teleporter",
+        container: false,
+        parse: |val, _, _| {
+            if val.is_null() {
+                return Ok(Curio::new(Teleporter(None)));
+            }
+            let dest = val.as_string().ok_or("Unable to parse value of teleporter".to_string())?;
+            Ok(Curio::new(Teleporter(Some(dest.to_string()))))
+        },
+        build: |_, _| Curio::new(Teleporter(None)),
+        default: Weight { weight: 0, min: 0, max: 0 },
+    },
+    Kind {
+        key: "quiver",
+        container: false,
+        parse: |val, _, _| Ok(Curio::new(Quiver(parse_val(val, "quiver")?))),
+        build: |val, _| Curio::new(Quiver(val)),
+        default: Weight { weight: 0, min: 1, max: 3 },
+    },
+];
+
+fn find_kind(key: &str) -> Option<&'static Kind> {
+    KINDS.iter().find(|k| k.key == key)
+}
+
+/// A curio's value: gold, damage, healing and so on, which is never negative.
+fn parse_val(json: &Json, key: &str) -> Result<i32> {
+    let val = json.as_i64().ok_or(format!("Unable to parse value of {}", key))?;
+    match i32::try_from(val) {
+        Ok(val) if val >= 0 => Ok(val),
+        _ => Err(format!("The value of {} must be between 0 and {}", key, i32::MAX)),
+    }
+}
+
+/// The largest weight a level may give a kind of curio, small enough that
+/// the weights of every kind add up without overflowing.
+const MAX_WEIGHT: u32 = 1_000_000;
+
+fn parse_weight(json: &Json, key: &str) -> Result<u32> {
+    let weight = json.as_u64().ok_or(format!("Unable to parse weight of {}", key))?;
+    if weight > MAX_WEIGHT as u64 {
+        return Err(format!("The weight of {} must be at most {}", key, MAX_WEIGHT));
+    }
+    Ok(weight as u32)
+}
+
+fn parse_inner(json: Option<&Json>, table: &CurioTable, rng: &mut StdRng) -> Result<Curio> {
+    match json {
+        Some(inner) => Curio::from_json(inner, table, rng),
+        None => Ok(table.generate_inner(rng)),
+    }
+}
+
+/// How likely a kind of curio is to turn up at random, and the range its
+/// value (gold, damage, healing, ...) is drawn from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weight {
+    pub weight: u32,
+    pub min: i32,
+    pub max: i32,
+}
+
+/// The odds of each kind of random curio. Levels can override them with a
+/// `curio_weights` object, e.g. `{"chest": 3, "potion": {"weight": 1, "max": 8}}`;
+/// kinds the level leaves out keep their defaults.
+#[derive(Clone, Debug)]
+pub struct CurioTable {
+    weights: BTreeMap<&'static str, Weight>,
+}
+
+impl Default for CurioTable {
+    fn default() -> CurioTable {
+        CurioTable { weights: KINDS.iter().map(|k| (k.key, k.default)).collect() }
+    }
+}
+
+impl CurioTable {
+    pub fn from_json(json: &Json) -> Result<CurioTable> {
+        let mut table = CurioTable::default();
+        let obj = json.as_object().ok_or("Unable to parse curio_weights".to_string())?;
+        for (key, val) in obj {
+            let kind = find_kind(key).ok_or(format!("Unknown curio {}", key))?;
+            let w = table.weights.get_mut(kind.key).unwrap();
+            if val.is_number() {
+                w.weight = parse_weight(val, key)?;
+                continue;
+            }
+            if !val.is_object() {
+                return Err(format!("Unable to parse weight of {}", key));
+            }
+            if let Some(weight) = val.find("weight") {
+                w.weight = parse_weight(weight, key)?;
+            }
+            if let Some(min) = val.find("min") {
+                w.min = parse_val(min, key)?;
+            }
+            if let Some(max) = val.find("max") {
+                w.max = parse_val(max, key)?;
+            }
+            if w.min > w.max {
+                return Err(format!("The range of {} is empty", key));
+            }
+            if w.max == i32::MAX {
+                return Err(format!("The range of {} is too large", key));
+            }
+        }
+        if !KINDS.iter().any(|k| !k.container && table.rolls(k)) {
+            return Err("At least one curio that is not a container needs a weight".to_string());
+        }
+        Ok(table)
+    }
+
+    pub fn weight(&self, key: &str) -> Option<Weight> {
+        self.weights.get(key).cloned()
+    }
+
+    /// Generate `n` random curios drawing from `rng`.
+    pub fn generate_n<R: Rng>(&self, rng: &mut R, n: usize) -> Vec<Curio> {
+        (0..n).map(|_| self.generate(rng)).collect()
+    }
+
+    /// Generate a random curio drawing from `rng`.
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Curio {
+        self.pick(rng, true)
+    }
+
+    /// Generate a random curio to go inside a container.
+    fn generate_inner<R: Rng>(&self, rng: &mut R) -> Curio {
+        self.pick(rng, false)
+    }
+
+    /// Whether `kind` can turn up at random. Locked chests only do if keys
+    /// can too, so a level that leaves out random keys gets no chest it
+    /// cannot open unless it places one itself.
+    fn rolls(&self, kind: &Kind) -> bool {
+        self.weights[kind.key].weight > 0
+            && (kind.key != "locked_chest" || self.weights["key"].weight > 0)
+    }
+
+    fn pick<R: Rng>(&self, rng: &mut R, containers: bool) -> Curio {
+        let choices: Vec<(&Kind, Weight)> = KINDS.iter()
+            .filter(|k| (containers || !k.container) && self.rolls(k))
+            .map(|k| (k, self.weights[k.key]))
+            .collect();
+        let total: u32 = choices.iter().map(|&(_, w)| w.weight).sum();
+        let mut roll = rng.gen_range(0, total);
+        for (kind, w) in choices {
+            if roll >= w.weight {
+                roll -= w.weight;
+                continue;
+            }
+            let val = rng.gen_range(w.min, w.max + 1);
+            let inner = if kind.container { Some(self.generate_inner(rng)) } else { None };
+            return (kind.build)(val, inner);
+        }
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, StdRng};
+    use rustc_serialize::json::{Json, ToJson};
+
+    use super::{Chest, Curio, CurioTable, Food, IronMaiden, Key, Teleporter};
+    use super::super::board::Result;
+
+    fn parse(s: &str) -> Result<Curio> {
+        let mut rng: StdRng = SeedableRng::from_seed(&[1][..]);
+        Curio::from_json(&Json::from_str(s).unwrap(), &CurioTable::default(), &mut rng)
+    }
+
+    #[test]
+    fn explicit_curios() {
+        assert_eq!(parse(r#"{"chest": 50}"#), Ok(Curio::new(Chest(50))));
+        assert_eq!(parse(r#"{"iron_maiden": {"dmg": 4, "inner": {"food": 3}}}"#),
+                   Ok(Curio::new(IronMaiden { dmg: 4, inner: Curio::new(Food(3)) })));
+        assert_eq!(parse(r#"{"fallen_adventurer": {"spike_trap": 2}}"#).unwrap().key(), "fallen_adventurer");
+        assert_eq!(parse(r#"{"key": null}"#), Ok(Curio::new(Key)));
+        assert_eq!(parse(r#"{"teleporter": "Vault"}"#), Ok(Curio::new(Teleporter(Some("Vault".to_string())))));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let curio = parse(r#"{"iron_maiden": {"dmg": 4, "inner": {"chest": 50}}}"#).unwrap();
+        assert_eq!(curio.to_json().to_string(), r#"{"iron_maiden":{"dmg":4,"inner":{"chest":50}}}"#);
+        for s in &[r#"{"potion":3}"#, r#"{"key":null}"#, r#"{"locked_chest":120}"#,
+                   r#"{"teleporter":null}"#, r#"{"quiver":2}"#] {
+            assert_eq!(&parse(s).unwrap().to_json().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn invalid_curios() {
+        assert!(parse(r#"{"dragon": 5}"#).is_err());
+        assert!(parse(r#"{"chest": "lots"}"#).is_err());
+        assert!(parse(r#"{"iron_maiden": {"inner": {"chest": 1}}}"#).is_err());
+        assert!(parse(r#"{"chest": 1, "food": 2}"#).is_err());
+        assert!(parse(r#"{"teleporter": 3}"#).is_err());
+        assert_eq!(parse(r#"{"spike_trap": -5}"#),
+                   Err("The value of spike_trap must be between 0 and 2147483647".to_string()));
+        assert!(parse(r#"{"chest": 4294967306}"#).is_err());
+        assert!(parse(r#"{"iron_maiden": {"dmg": -1, "inner": {"chest": 1}}}"#).is_err());
+    }
+
+    #[test]
+    fn weighted_generation() {
+        let parse_table = |s: &str| CurioTable::from_json(&Json::from_str(s).unwrap());
+        let table = parse_table(r#"{"chest": 0, "spike_trap": 0, "food": 0, "iron_maiden": 0,
+                                    "fallen_adventurer": 0, "quiver": {"weight": 1, "min": 4, "max": 6}}"#).unwrap();
+        let mut rng: StdRng = SeedableRng::from_seed(&[7][..]);
+        for c in table.generate_n(&mut rng, 50) {
+            let n = c.to_json().find("quiver").and_then(|n| n.as_i64()).unwrap();
+            assert!((4..=6).contains(&n));
+        }
+
+        assert!(parse_table(r#"{"dragon": 1}"#).is_err());
+        assert!(parse_table(r#"{"chest": {"min": 5, "max": 1}}"#).is_err());
+        assert!(parse_table(r#"{"chest": {"max": 2147483647}}"#).is_err());
+        assert!(parse_table(r#"{"chest": {"max": 2147483646}}"#).is_ok());
+        assert!(parse_table(r#"{"chest": {"min": -3}}"#).is_err());
+        assert!(parse_table(r#"{"chest": 4294967297}"#).is_err());
+        assert!(parse_table(r#"{"chest": {"weight": 1000001}}"#).is_err());
+        assert!(parse_table(r#"{"chest": -1}"#).is_err());
+        assert!(parse_table(r#"{"chest": 1000000, "food": 1000000, "spike_trap": 1000000}"#).is_ok());
+        assert!(parse_table(r#"{"chest": 0, "spike_trap": 0, "food": 0}"#).is_err());
+    }
+
+    #[test]
+    fn no_locked_chests_without_keys() {
+        let parse_table = |s: &str| CurioTable::from_json(&Json::from_str(s).unwrap());
+        let table = parse_table(r#"{"locked_chest": 100, "food": 1}"#).unwrap();
+        let mut rng: StdRng = SeedableRng::from_seed(&[3][..]);
+        assert!(table.generate_n(&mut rng, 200).iter().all(|c| c.key() != "locked_chest"));
+
+        let table = parse_table(r#"{"locked_chest": 100, "key": 1}"#).unwrap();
+        assert!(table.generate_n(&mut rng, 200).iter().any(|c| c.key() == "locked_chest"));
+
+        assert!(parse_table(r#"{"chest": 0, "spike_trap": 0, "food": 0, "locked_chest": 1}"#).is_err());
+    }
+}
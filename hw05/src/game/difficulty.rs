@@ -0,0 +1,63 @@
+use std::cmp;
+
+/// How hard a game is: how much HP and how many arrows the player starts
+/// with, and how hard traps hit and how well food heals.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Difficulty {
+    pub name: &'static str,
+    pub max_hp: i32,
+    pub arrows: i32,
+    /// Multiplies the damage of traps.
+    pub trap_damage: f64,
+    /// Multiplies the HP food heals.
+    pub food_healing: f64,
+}
+
+pub const EASY: Difficulty = Difficulty { name: "easy", max_hp: 35, arrows: 7, trap_damage: 0.5, food_healing: 1.5 };
+pub const NORMAL: Difficulty = Difficulty { name: "normal", max_hp: 25, arrows: 5, trap_damage: 1.0, food_healing: 1.0 };
+pub const HARD: Difficulty = Difficulty { name: "hard", max_hp: 15, arrows: 3, trap_damage: 1.5, food_healing: 0.5 };
+
+impl Default for Difficulty {
+    fn default() -> Difficulty {
+        NORMAL
+    }
+}
+
+impl Difficulty {
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        [EASY, NORMAL, HARD].iter().cloned().find(|d| d.name == name.to_lowercase())
+    }
+
+    /// The damage a trap worth `dmg` does. Traps always hurt a little.
+    pub fn trap(&self, dmg: i32) -> i32 {
+        scale(dmg, self.trap_damage)
+    }
+
+    /// The HP food worth `heal` restores. Food always helps a little.
+    pub fn food(&self, heal: i32) -> i32 {
+        scale(heal, self.food_healing)
+    }
+}
+
+fn scale(val: i32, by: f64) -> i32 {
+    if val <= 0 {
+        return val;
+    }
+    cmp::max(1, (val as f64 * by).round() as i32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Difficulty, EASY, HARD, NORMAL};
+
+    #[test]
+    fn presets() {
+        assert_eq!(Difficulty::from_name("Hard"), Some(HARD));
+        assert_eq!(Difficulty::from_name("nightmare"), None);
+        assert_eq!(Difficulty::default(), NORMAL);
+        assert_eq!((NORMAL.trap(7), NORMAL.food(7)), (7, 7));
+        assert_eq!((EASY.trap(7), EASY.food(7)), (4, 11));
+        assert_eq!((HARD.trap(7), HARD.food(1)), (11, 1));
+        assert_eq!(HARD.trap(0), 0);
+    }
+}
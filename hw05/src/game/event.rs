@@ -0,0 +1,110 @@
+use std::fmt;
+
+/// Something that happened to the player while carrying out a command. Front
+/// ends decide how to show events; `Display` gives the standard text in the
+/// current language.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Chest(i32),
+    SpikeTrap(i32),
+    Food(i32),
+    IronMaiden(i32),
+    FallenAdventurer,
+    Potion(i32),
+    Key,
+    LockedChest(i32),
+    ChestLocked,
+    Teleported(String),
+    Quiver(i32),
+    /// Text from the level: a room's first-visit text or a trigger's message.
+    Message(String),
+    Damage(i32),
+    Healed(i32),
+    Gold(i32),
+    ItemGained(String),
+    ItemLost(String),
+    HallOpened(String),
+    /// The player came upon a monster in the room they entered.
+    MonsterFound(String),
+    /// A monster wandered into the player's room.
+    MonsterArrived(String),
+    MonsterAttacked(String, i32),
+    /// The player struck a monster for some damage.
+    MonsterHit(String, i32),
+    MonsterKilled(String),
+    /// The player fled from a fight into a room.
+    Fled(String),
+    /// The player walked into a room on the way somewhere.
+    Walked(String),
+    /// The player stopped before reaching the room they were travelling to.
+    TravelStopped(String),
+    EatenByWumpus,
+    FellInPit,
+    CarriedByBats(String),
+    WumpusKilled,
+    ArrowMissed,
+    WumpusAttacked,
+    QuiverEmpty,
+    TimePasses,
+}
+
+/// A warning about a hazard in a neighboring room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sense {
+    Wumpus,
+    Draft,
+    Flapping,
+}
+
+impl fmt::Display for Sense {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let id = match *self {
+            Sense::Wumpus => "sense.wumpus",
+            Sense::Draft => "sense.draft",
+            Sense::Flapping => "sense.flapping",
+        };
+        write!(f, "{}", t!(id))
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match *self {
+            Event::Chest(gold) => t!("event.chest", gold = gold),
+            Event::SpikeTrap(dmg) => t!("event.spike_trap", damage = dmg),
+            Event::Food(heal) => t!("event.food", hp = heal),
+            Event::IronMaiden(dmg) => t!("event.iron_maiden", damage = dmg),
+            Event::FallenAdventurer => t!("event.fallen_adventurer"),
+            Event::Potion(hp) => t!("event.potion", hp = hp),
+            Event::Key => t!("event.key"),
+            Event::LockedChest(gold) => t!("event.locked_chest", gold = gold),
+            Event::ChestLocked => t!("event.chest_locked"),
+            Event::Teleported(ref room) => t!("event.teleported", room = room),
+            Event::Quiver(n) => t!("event.quiver", arrows = n),
+            Event::Message(ref text) => return write!(f, "{}", text),
+            Event::Damage(dmg) => t!("event.damage", damage = dmg),
+            Event::Healed(hp) => t!("event.healed", hp = hp),
+            Event::Gold(gold) => t!("event.gold", gold = gold),
+            Event::ItemGained(ref item) => t!("event.item_gained", item = item),
+            Event::ItemLost(ref item) => t!("event.item_lost", item = item),
+            Event::HallOpened(ref room) => t!("event.hall_opened", room = room),
+            Event::MonsterFound(ref name) => t!("event.monster_found", name = name),
+            Event::MonsterArrived(ref name) => t!("event.monster_arrived", name = name),
+            Event::MonsterAttacked(ref name, dmg) => t!("event.monster_attacked", name = name, damage = dmg),
+            Event::MonsterHit(ref name, dmg) => t!("event.monster_hit", name = name, damage = dmg),
+            Event::MonsterKilled(ref name) => t!("event.monster_killed", name = name),
+            Event::Fled(ref room) => t!("event.fled", room = room),
+            Event::Walked(ref room) => t!("event.walked", room = room),
+            Event::TravelStopped(ref room) => t!("event.travel_stopped", room = room),
+            Event::EatenByWumpus => t!("event.eaten_by_wumpus"),
+            Event::FellInPit => t!("event.fell_in_pit"),
+            Event::CarriedByBats(ref room) => t!("event.carried_by_bats", room = room),
+            Event::WumpusKilled => t!("event.wumpus_killed"),
+            Event::ArrowMissed => t!("event.arrow_missed"),
+            Event::WumpusAttacked => t!("event.wumpus_attacked"),
+            Event::QuiverEmpty => t!("event.quiver_empty"),
+            Event::TimePasses => t!("event.time_passes"),
+        };
+        write!(f, "{}", text)
+    }
+}
@@ -0,0 +1,161 @@
+use std::cmp;
+
+use rand::Rng;
+
+use super::board::{self, Board};
+use super::curio::CurioTable;
+use super::room::{Room, RoomId};
+
+const ROOM_NAMES: &[&str] = &[
+    "Entry", "Foyer", "Library", "Chamber", "Kitchen", "Pantry", "Dungeon", "Lair",
+    "Treasury", "Sewer", "Armory", "Chapel", "Crypt", "Gallery", "Cellar", "Tower",
+    "Study", "Barracks", "Vault", "Passage",
+];
+
+/// Knobs for `Board::generate`.
+#[derive(Clone, Debug)]
+pub struct Params {
+    pub rooms: usize,
+    /// Average number of halls leading out of each room.
+    pub degree: f64,
+    /// Average number of curios in each room.
+    pub curio_density: f64,
+    pub pits: usize,
+    pub bats: usize,
+    /// The odds of each kind of curio.
+    pub curios: CurioTable,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params { rooms: 20, degree: 3.0, curio_density: 2.0, pits: 2, bats: 2, curios: CurioTable::default() }
+    }
+}
+
+impl Board {
+    /// Build a random castle. Every room is reachable from the spawn location
+    /// without crossing a pit, and the wumpus is placed away from the spawn.
+    /// The same `params` and `seed` always give the same board.
+    pub fn generate(params: &Params, seed: u64) -> Board {
+        let mut rng = board::seeded_rng(seed);
+        let n = cmp::max(params.rooms, 2);
+        let mut board = Board::with_seed(seed);
+
+        // Rooms; the spawn room starts empty.
+        for i in 0..n {
+            let name = room_name(i);
+            let count = if i == 0 { 0 } else { (params.curio_density * 2.0 * rng.gen::<f64>()).round() as usize };
+            board.add_room(Room::new(&name, params.curios.generate_n(&mut rng, count), false));
+        }
+
+        // A random spanning tree keeps the castle connected, then extra halls
+        // bring the average degree up to the requested one.
+        let mut adjacent = vec![vec![false; n]; n];
+        for i in 1..n {
+            let j = rng.gen_range(0, i);
+            connect(&mut board, &mut adjacent, i, j);
+        }
+        let max_halls = n * (n - 1) / 2;
+        let target = cmp::min(max_halls, (params.degree * n as f64 / 2.0).round() as usize);
+        while board.halls().len() < target {
+            let i = rng.gen_range(0, n);
+            let j = rng.gen_range(0, n);
+            if i != j && !adjacent[i][j] {
+                connect(&mut board, &mut adjacent, i, j);
+            }
+        }
+
+        // The wumpus lurks somewhere other than the spawn room, away from it
+        // if possible.
+        let spawn = board.spawn_location();
+        let far: Vec<RoomId> = board.room_ids().into_iter()
+            .filter(|&r| r != spawn && !board.neighbors(spawn).contains(&r))
+            .collect();
+        let wumpus = match rng.choose(&far) {
+            Some(&r) => r,
+            None => RoomId(rng.gen_range(1, n)),
+        };
+        board.room_mut(wumpus).wumpus = true;
+
+        // Hazards go in distinct rooms. A pit may not cut the safe rooms apart.
+        let mut free: Vec<RoomId> = board.room_ids().into_iter().filter(|&r| r != spawn && r != wumpus).collect();
+        rng.shuffle(&mut free);
+        let mut pits = 0;
+        let mut i = 0;
+        while pits < params.pits && i < free.len() {
+            let r = free[i];
+            board.room_mut(r).pit = true;
+            if safe_rooms_connected(&board) {
+                free.remove(i);
+                pits += 1;
+            } else {
+                board.room_mut(r).pit = false;
+                i += 1;
+            }
+        }
+        for &r in free.iter().take(params.bats) {
+            board.room_mut(r).bats = true;
+        }
+
+        board
+    }
+}
+
+fn room_name(i: usize) -> String {
+    if i < ROOM_NAMES.len() {
+        ROOM_NAMES[i].to_string()
+    } else {
+        format!("Cave{}", i - ROOM_NAMES.len() + 1)
+    }
+}
+
+fn connect(board: &mut Board, adjacent: &mut [Vec<bool>], i: usize, j: usize) {
+    adjacent[i][j] = true;
+    adjacent[j][i] = true;
+    board.add_hall(RoomId(i), RoomId(j));
+}
+
+/// Whether every room without a pit can be reached from the spawn location
+/// without walking through a pit.
+fn safe_rooms_connected(board: &Board) -> bool {
+    let mut seen = vec![false; board.len()];
+    let mut stack = vec![board.spawn_location()];
+    seen[board.spawn_location().0] = true;
+    while let Some(r) = stack.pop() {
+        for &n in board.neighbors(r) {
+            if !seen[n.0] && !board.room(n).pit {
+                seen[n.0] = true;
+                stack.push(n);
+            }
+        }
+    }
+    board.room_ids().into_iter().all(|r| seen[r.0] || board.room(r).pit)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{safe_rooms_connected, Params};
+    use super::super::board::Board;
+
+    #[test]
+    fn deterministic() {
+        let params = Params::default();
+        let a = Board::generate(&params, 7).to_json();
+        assert_eq!(a, Board::generate(&params, 7).to_json());
+        assert!(a != Board::generate(&params, 8).to_json());
+    }
+
+    #[test]
+    fn connected_with_hazards() {
+        for seed in 0..50 {
+            let params = Params { rooms: 12, degree: 2.5, curio_density: 1.0, pits: 3, bats: 2, ..Params::default() };
+            let board = Board::generate(&params, seed);
+            assert_eq!(board.len(), 12);
+            assert!(board.wumpus_location().is_some());
+            assert!(safe_rooms_connected(&board));
+            assert_eq!(board.room_ids().into_iter().filter(|&r| board.room(r).pit).count(), 3);
+            assert_eq!(board.room_ids().into_iter().filter(|&r| board.room(r).bats).count(), 2);
+            assert!(board.halls().len() >= 11);
+        }
+    }
+}
@@ -1,28 +1,25 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use super::room::RoomId;
 
-use super::room::Room;
-
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Hall {
-    pub left: Rc<RefCell<Room>>,
-    pub right: Rc<RefCell<Room>>,
+    pub left: RoomId,
+    pub right: RoomId,
 }
 
 impl Hall {
-    pub fn new(left: Rc<RefCell<Room>>, right: Rc<RefCell<Room>>) -> Hall {
-	Hall { left: left.clone(), right: right.clone() }
+    pub fn new(left: RoomId, right: RoomId) -> Hall {
+        Hall { left, right }
     }
 
-    /// Given a Room `room`, find the room at the other end of Hall `self`.
-    pub fn other(&self, room: &Room) -> Rc<RefCell<Room>> {
-	if room.name == self.left.borrow().name {
-	    self.right.clone()
-	} else if room.name == self.right.borrow().name {
-	    self.left.clone()
-	} else {
-	    panic!("Invalid room in hall");
-	}
+    /// Given a room `room`, find the room at the other end of Hall `self`, or
+    /// `None` if the hall does not touch `room`.
+    pub fn other(&self, room: RoomId) -> Option<RoomId> {
+        if room == self.left {
+            Some(self.right)
+        } else if room == self.right {
+            Some(self.left)
+        } else {
+            None
+        }
     }
-
 }
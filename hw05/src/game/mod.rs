@@ -1,5 +1,15 @@
+pub mod agent;
 pub mod board;
+pub mod campaign;
 pub mod curio;
+pub mod difficulty;
+pub mod event;
+pub mod generate;
 pub mod hall;
+pub mod monster;
 pub mod room;
 pub mod player;
+pub mod replay;
+pub mod save;
+pub mod score;
+pub mod trigger;
@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use rand::{Rng, StdRng};
+use rustc_serialize::json::{Json, ToJson};
+
+use super::board::{self, Board, Result};
+use super::curio::{Curio, CurioTable};
+use super::event::Event;
+use super::player::Player;
+use super::room::RoomId;
+
+/// The most damage the player does with one blow in a fight.
+pub const PLAYER_ATTACK: i32 = 6;
+
+/// A creature that wanders the castle, a room each turn, and attacks the
+/// player whenever it shares a room with them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Monster {
+    pub name: String,
+    pub hp: i32,
+    /// The most damage one of its blows does.
+    pub attack: i32,
+    /// The curios it drops when it is killed.
+    pub loot: Vec<Curio>,
+}
+
+impl Monster {
+    pub fn new(name: &str, hp: i32, attack: i32, loot: Vec<Curio>) -> Monster {
+        Monster { name: name.to_string(), hp, attack, loot }
+    }
+
+    /// Parse a monster written like
+    /// `{"name": "ghoul", "hp": 8, "attack": 3, "loot": [{"chest": 20}]}`.
+    /// Like a room's curios, the loot is a list or a number of random
+    /// curios, and defaults to one random curio.
+    pub fn from_json(json: &Json, table: &CurioTable, rng: &mut StdRng) -> Result<Monster> {
+        let name = json.find("name").and_then(|n| n.as_string()).ok_or("Unable to parse monster name".to_string())?;
+        let int = |key: &str| {
+            json.find(key).and_then(|v| v.as_i64()).map(|v| v as i32).ok_or(format!("Unable to parse {} of {}", key, name))
+        };
+        let (hp, attack) = (int("hp")?, int("attack")?);
+        if hp <= 0 || attack <= 0 {
+            return Err(format!("{} needs positive hp and attack", name));
+        }
+        let loot = match json.find("loot") {
+            Some(json_loot) => board::parse_curios(json_loot, table, rng)?,
+            None => table.generate_n(rng, 1),
+        };
+        Ok(Monster::new(name, hp, attack, loot))
+    }
+}
+
+impl ToJson for Monster {
+    fn to_json(&self) -> Json {
+        let mut obj = BTreeMap::new();
+        obj.insert("name".to_string(), self.name.to_json());
+        obj.insert("hp".to_string(), self.hp.to_json());
+        obj.insert("attack".to_string(), self.attack.to_json());
+        obj.insert("loot".to_string(), self.loot.to_json());
+        Json::Object(obj)
+    }
+}
+
+/// Let a turn pass for the monsters. Those in the player's room attack;
+/// the rest wander into a neighboring room, steering clear of pits. In a
+/// shared game, the monsters in rooms where `others` stand stay put and
+/// strike at those players on their own turns, so each player is attacked
+/// once per command they give however many others are playing.
+pub fn tick(board: &mut Board, player: &mut Player, others: &[RoomId], events: &mut Vec<Event>) {
+    if player.hp <= 0 || player.won {
+        return;
+    }
+    let here = player.location;
+    attack(board, player, events);
+
+    // Pick every move before making any, so no monster moves twice.
+    let mut moves = Vec::new();
+    for room in board.room_ids().into_iter().filter(|&r| r != here && !others.contains(&r)) {
+        let exits: Vec<RoomId> = board.neighbors(room).iter().cloned().filter(|&r| !board.room(r).pit).collect();
+        for i in 0..board.room(room).monsters.len() {
+            if let Some(&dest) = board.rng().choose(&exits) {
+                moves.push((room, i, dest));
+            }
+        }
+    }
+    let mut moving = Vec::new();
+    for &(from, i, dest) in moves.iter().rev() {
+        moving.push((board.room_mut(from).monsters.remove(i), dest));
+    }
+    for (monster, dest) in moving.into_iter().rev() {
+        if dest == here {
+            events.push(Event::MonsterArrived(monster.name.clone()));
+        }
+        board.room_mut(dest).monsters.push(monster);
+    }
+}
+
+/// The monsters in the player's room each strike at them.
+fn attack(board: &mut Board, player: &mut Player, events: &mut Vec<Event>) {
+    let here = player.location;
+    for i in 0..board.room(here).monsters.len() {
+        if player.hp <= 0 {
+            return;
+        }
+        let (name, attack) = {
+            let monster = &board.room(here).monsters[i];
+            (monster.name.clone(), monster.attack)
+        };
+        let dmg = board.rng().gen_range(1, attack + 1);
+        events.push(Event::MonsterAttacked(name, dmg));
+        player.hurt(dmg);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rustc_serialize::json::{Json, ToJson};
+
+    use super::Monster;
+    use super::super::board::{self, Board};
+    use super::super::curio::{Chest, Curio, CurioTable};
+    use super::super::event::Event;
+    use super::super::player::{Command, Player};
+    use super::super::room::Room;
+
+    #[test]
+    fn parse_monsters() {
+        let mut rng = board::seeded_rng(1);
+        let table = CurioTable::default();
+        let json = Json::from_str(r#"{"name": "ghoul", "hp": 8, "attack": 3, "loot": [{"chest": 20}]}"#).unwrap();
+        let ghoul = Monster::from_json(&json, &table, &mut rng).unwrap();
+        assert_eq!(ghoul, Monster::new("ghoul", 8, 3, vec![Curio::new(Chest(20))]));
+        assert_eq!(Monster::from_json(&ghoul.to_json(), &table, &mut rng), Ok(ghoul));
+
+        let json = Json::from_str(r#"{"name": "rat", "hp": 2, "attack": 1}"#).unwrap();
+        assert_eq!(Monster::from_json(&json, &table, &mut rng).unwrap().loot.len(), 1);
+        let json = Json::from_str(r#"{"name": "ghost", "hp": 0, "attack": 1}"#).unwrap();
+        assert!(Monster::from_json(&json, &table, &mut rng).is_err());
+    }
+
+    #[test]
+    fn fight_and_flee() {
+        let mut board = Board::with_seed(7);
+        let entry = board.add_room(Room::new("Entry", Vec::new(), false));
+        let den = board.add_room(Room::new("Den", Vec::new(), false));
+        board.add_hall(entry, den);
+        board.room_mut(den).monsters.push(Monster::new("ghoul", 100, 1, Vec::new()));
+
+        let mut player = Player::new(entry);
+        let events = player.act(&mut board, Command::Go("den".to_string())).unwrap();
+        assert_eq!(events, vec![Event::MonsterFound("ghoul".to_string()), Event::MonsterAttacked("ghoul".to_string(), 1)]);
+        assert!(player.act(&mut board, Command::Go("entry".to_string())).is_err());
+        let events = player.act(&mut board, Command::Fight).unwrap();
+        assert_eq!(events[1], Event::MonsterAttacked("ghoul".to_string(), 1));
+        assert!(board.room(den).monsters[0].hp < 100);
+        assert_eq!(player.hp, 23);
+
+        // The ghoul follows the player out, since the den has no other exit.
+        let events = player.act(&mut board, Command::Flee).unwrap();
+        assert_eq!(events, vec![Event::Fled("Entry".to_string()), Event::MonsterArrived("ghoul".to_string())]);
+        assert_eq!(player.location, entry);
+        assert_eq!(board.room(entry).monsters.len(), 1);
+
+        board.room_mut(entry).monsters.clear();
+        assert!(player.act(&mut board, Command::Flee).is_err());
+        assert!(player.act(&mut board, Command::Fight).is_err());
+    }
+
+    #[test]
+    fn monsters_drop_loot_and_wander() {
+        let mut board = Board::with_seed(3);
+        let entry = board.add_room(Room::new("Entry", Vec::new(), false));
+        let hall = board.add_room(Room::new("Hall", Vec::new(), false));
+        let pit = board.add_room(Room::new("Pit", Vec::new(), false));
+        board.room_mut(pit).pit = true;
+        board.add_hall(entry, hall);
+        board.add_hall(hall, pit);
+        board.room_mut(entry).monsters.push(Monster::new("rat", 1, 1, vec![Curio::new(Chest(15))]));
+        board.room_mut(pit).monsters.push(Monster::new("bat", 1, 1, Vec::new()));
+
+        let mut player = Player::new(entry);
+        let events = player.act(&mut board, Command::Fight).unwrap();
+        assert_eq!(events[1..], [Event::MonsterKilled("rat".to_string()), Event::Chest(15)]);
+        assert_eq!(player.gold, 15);
+        assert!(board.room(entry).monsters.is_empty());
+
+        // The bat leaves the pit for the hall, and the hall for the entry,
+        // never flying back into the pit.
+        assert_eq!(board.room(hall).monsters.len(), 1);
+        let events = player.act(&mut board, Command::Wait).unwrap();
+        assert_eq!(events, vec![Event::TimePasses, Event::MonsterArrived("bat".to_string())]);
+        let events = player.act(&mut board, Command::Wait).unwrap();
+        assert_eq!(events, vec![Event::TimePasses, Event::MonsterAttacked("bat".to_string(), 1)]);
+        assert!(board.room(pit).monsters.is_empty());
+    }
+}
@@ -1,103 +1,605 @@
 use std;
 use std::fmt;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::collections::BTreeMap;
 
-use super::curio::Curio;
-use super::room::Room;
+use rand::Rng;
 
-const MAX_HP: i32 = 25;
+use super::board::{Board, Result};
+use super::curio::{Curio, Finder};
+use super::difficulty::Difficulty;
+use super::event::Event;
+use super::monster::{self, PLAYER_ATTACK};
+use super::room::RoomId;
+use super::score::RunStats;
+use super::trigger;
 
+/// Directions the player can name exits by: `north` is the first exit,
+/// `east` the second and so on. The castle has no compass, so beyond the
+/// fourth exit only numbers and names work.
+pub const DIRECTIONS: [&str; 4] = ["north", "east", "south", "west"];
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum Command {
     Go(String),
     Shoot(String),
+    /// Walk the shortest known way to a room the player has been to.
+    Travel(String),
+    /// Attack a monster in the player's room.
+    Fight,
+    /// Run from a fight, back the way the player came.
+    Flee,
+    Wait,
+}
+
+impl fmt::Display for Command {
+    /// The command as it would be typed.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Command::Go(ref room) => write!(f, "go {}", room),
+            Command::Shoot(ref room) => write!(f, "shoot {}", room),
+            Command::Travel(ref room) => write!(f, "travel {}", room),
+            Command::Fight => write!(f, "fight"),
+            Command::Flee => write!(f, "flee"),
+            Command::Wait => write!(f, "wait"),
+        }
+    }
 }
 
 pub struct Player {
-    pub location: Rc<RefCell<Room>>,
+    pub location: RoomId,
+    /// The room the player last walked in from.
+    pub previous: Option<RoomId>,
     pub hp: i32,
+    pub max_hp: i32,
     pub gold: i32,
-    won: bool,
+    pub arrows: i32,
+    /// Things the player is carrying, such as keys, and how many of each.
+    pub items: BTreeMap<String, u32>,
+    pub won: bool,
+    pub difficulty: Difficulty,
+    pub stats: RunStats,
 }
 
 impl Player {
-    pub fn new(location: Rc<RefCell<Room>>) -> Player {
+    pub fn new(location: RoomId) -> Player {
+        Player::with_difficulty(location, Difficulty::default())
+    }
+
+    pub fn with_difficulty(location: RoomId, difficulty: Difficulty) -> Player {
         Player {
-            location: location,
-            hp: MAX_HP,
+            location,
+            previous: None,
+            hp: difficulty.max_hp,
+            max_hp: difficulty.max_hp,
             gold: 0,
+            arrows: difficulty.arrows,
+            items: BTreeMap::new(),
             won: false,
+            difficulty,
+            stats: RunStats::default(),
         }
     }
 
-    pub fn use_curio(&mut self, curio: Curio) {
-        match curio {
-            Curio::Chest(gold) => {
-                println!("You open the chest and gain {} gold.", gold);
-                self.gold += gold;
+    /// Start a new game: place a player in the board's spawn room, which
+    /// counts as their first visit there.
+    pub fn spawn(board: &mut Board, difficulty: Difficulty) -> (Player, Vec<Event>) {
+        let player = Player::with_difficulty(board.spawn_location(), difficulty);
+        player.arrive(board)
+    }
+
+    /// Start the next level of a campaign on `board`, keeping this player's
+    /// gold and HP.
+    pub fn next_level(&self, board: &mut Board) -> (Player, Vec<Event>) {
+        let mut player = Player::with_difficulty(board.spawn_location(), self.difficulty);
+        player.gold = self.gold;
+        player.hp = self.hp;
+        player.max_hp = self.max_hp;
+        player.arrive(board)
+    }
+
+    fn arrive(mut self, board: &mut Board) -> (Player, Vec<Event>) {
+        let mut events = Vec::new();
+        let spawn = board.spawn_location();
+        self.enter(board, spawn, &mut events);
+        (self, events)
+    }
+
+    pub fn hurt(&mut self, dmg: i32) {
+        self.hp -= dmg;
+        self.stats.damage_taken += dmg;
+    }
+
+    /// Restore up to `hp` HP without going over the player's maximum,
+    /// returning how much was restored.
+    pub fn heal(&mut self, hp: i32) -> i32 {
+        let healed = std::cmp::max(0, std::cmp::min(hp, self.max_hp - self.hp));
+        self.hp += healed;
+        self.stats.hp_healed += healed;
+        healed
+    }
+
+    pub fn add_item(&mut self, item: &str) {
+        *self.items.entry(item.to_string()).or_insert(0) += 1;
+    }
+
+    /// Use up one `item`, returning false if the player has none.
+    pub fn take_item(&mut self, item: &str) -> bool {
+        match self.items.get_mut(item) {
+            Some(n) if *n > 0 => *n -= 1,
+            _ => return false,
+        }
+        if self.items[item] == 0 {
+            self.items.remove(item);
+        }
+        true
+    }
+
+    /// Take all of the player's remaining HP.
+    pub fn kill(&mut self) {
+        let hp = self.hp;
+        self.hurt(hp);
+    }
+
+    /// Execute the given command on the player and board state, returning
+    /// what happened. Every command that succeeds takes a turn, after which
+    /// the monsters take theirs.
+    pub fn act(&mut self, board: &mut Board, cmd: Command) -> Result<Vec<Event>> {
+        self.act_among(board, cmd, &[])
+    }
+
+    /// Like `act`, in a game shared with other players standing in `others`.
+    pub fn act_among(&mut self, board: &mut Board, cmd: Command, others: &[RoomId]) -> Result<Vec<Event>> {
+        let mut events = Vec::new();
+        match cmd {
+            Command::Go(rname) => {
+                let new_room = self.find_room(board, &rname)?;
+                self.check_unblocked(board)?;
+                self.previous = Some(self.location);
+                self.enter(board, new_room, &mut events);
             },
-            Curio::SpikeTrap(dmg) => {
-                println!("You take {} damage from the spikes.", dmg);
-                self.hp -= dmg;
+            Command::Travel(rname) => {
+                let dest = self.find_known_room(board, &rname)?;
+                self.check_unblocked(board)?;
+                self.travel(board, dest, others, &mut events)?;
+                return Ok(events);
             },
-            Curio::Food(heal) => {
-                println!("You shove a wall chicken into your gob and heal {} HP.", heal);
-                self.hp = std::cmp::min(MAX_HP, self.hp + heal);
+            Command::Fight => self.fight(board, &mut events)?,
+            Command::Flee => {
+                if board.room(self.location).monsters.is_empty() {
+                    return Err(t!("player.nothing_to_flee"));
+                }
+                let exits = board.neighbors(self.location).to_vec();
+                let dest = match self.previous {
+                    Some(room) if exits.contains(&room) => Some(room),
+                    _ => board.rng().choose(&exits).cloned(),
+                };
+                let dest = dest.ok_or_else(|| t!("player.no_way_back"))?;
+                self.previous = Some(self.location);
+                events.push(Event::Fled(board.room(dest).name.clone()));
+                self.enter(board, dest, &mut events);
             },
-            Curio::IronMaiden(sub, dmg) => {
-                println!("Dude I love Iron Maiden! This one's pointy, though.");
-                println!("You cut yourself on the spikes inside for {} damage.", dmg);
-                self.hp -= dmg;
-                println!("You open the iron maiden and...");
-                self.use_curio(*sub);
+            Command::Wait => {
+                events.push(Event::TimePasses);
             },
-            Curio::FallenAdventurer(sub) => {
-                println!("You pilfer the corpse and...");
-                self.use_curio(*sub);
+            Command::Shoot(rname) => {
+                let room = self.find_room(board, &rname)?;
+                if self.arrows <= 0 {
+                    events.push(Event::QuiverEmpty);
+                    return Ok(events);
+                }
+                self.arrows -= 1;
+                if board.room(room).wumpus {
+                    events.push(Event::WumpusKilled);
+                    board.room_mut(room).wumpus = false;
+                    self.won = true;
+                    self.stats.wumpus_kills += 1;
+                } else {
+                    events.push(Event::ArrowMissed);
+                    self.startle_wumpus(board, &mut events);
+                }
             },
         }
+        monster::tick(board, self, others, &mut events);
+        self.stats.turns += 1;
+        Ok(events)
     }
 
-    /// Execute the given command on the player and board state.
-    pub fn act(&mut self, cmd: Command) -> Result<(), ()> {
-	match cmd {
-	    Command::Go(rname) => {
-		let new_room = try!(self.find_room(rname));
-		for c in new_room.borrow().contents.iter() {
-                    self.use_curio(c.clone());
-		}
-		self.location = new_room.clone();
-		Ok(())
-	    },
-	    Command::Shoot(rname) => {
-		let room = try!(self.find_room(rname));
-		let mut room = room.borrow_mut();
-		if room.wumpus {
-		    room.wumpus = false;
-                    self.won = true;
-		}
-		Ok(())
-	    },
-	}
+    /// Monsters in the player's room keep them from walking away.
+    fn check_unblocked(&self, board: &Board) -> Result<()> {
+        match board.room(self.location).monsters.first() {
+            Some(monster) => Err(t!("player.monster_blocks", name = monster.name)),
+            None => Ok(()),
+        }
+    }
+
+    /// Strike the first monster in the player's room. If it dies, the player
+    /// picks up its loot.
+    fn fight(&mut self, board: &mut Board, events: &mut Vec<Event>) -> Result<()> {
+        let here = self.location;
+        if board.room(here).monsters.is_empty() {
+            return Err(t!("player.nothing_to_fight"));
+        }
+        let dmg = board.rng().gen_range(1, PLAYER_ATTACK + 1);
+        let killed = {
+            let monster = &mut board.room_mut(here).monsters[0];
+            monster.hp -= dmg;
+            events.push(Event::MonsterHit(monster.name.clone(), dmg));
+            monster.hp <= 0
+        };
+        if killed {
+            let monster = board.room_mut(here).monsters.remove(0);
+            events.push(Event::MonsterKilled(monster.name));
+            self.stats.monster_kills += 1;
+            self.take_curios(board, here, monster.loot, events);
+        }
+        Ok(())
+    }
+
+    /// Walk to `dest` along the shortest path through rooms the player has
+    /// visited, a turn per room. The player stops early if they are hurt,
+    /// carried off, or reach a room with a hazard warning or a monster, so
+    /// nothing bad happens on the way without them getting to react.
+    fn travel(&mut self, board: &mut Board, dest: RoomId, others: &[RoomId], events: &mut Vec<Event>) -> Result<()> {
+        let name = board.room(dest).name.clone();
+        if dest == self.location {
+            return Err(t!("player.already_there", room = name));
+        }
+        let visited = &self.stats.rooms_visited;
+        let path = board.find_path(self.location, |r| visited.contains(&r), |r| r == dest)
+            .ok_or_else(|| t!("player.no_known_way", room = name))?;
+        for (i, &room) in path.iter().enumerate() {
+            let hp = self.hp;
+            self.previous = Some(self.location);
+            events.push(Event::Walked(board.room(room).name.clone()));
+            self.enter(board, room, events);
+            monster::tick(board, self, others, events);
+            self.stats.turns += 1;
+            let arrived = i + 1 == path.len();
+            if arrived || self.hp <= 0 {
+                break;
+            }
+            if self.hp < hp || self.location != room || !board.senses(room).is_empty() ||
+               !board.room(room).monsters.is_empty() {
+                events.push(Event::TravelStopped(name));
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Find a room the player has visited by its name or the unique prefix
+    /// of one. Case insensitive.
+    pub fn find_known_room(&self, board: &Board, rname: &str) -> Result<RoomId> {
+        let known = &self.stats.rooms_visited;
+        if let Some(room) = board.room_named(rname).filter(|r| known.contains(r)) {
+            return Ok(room);
+        }
+        let rname = rname.to_lowercase();
+        let prefixed: Vec<RoomId> = known.iter().cloned()
+            .filter(|&r| board.room(r).name.to_lowercase().starts_with(&rname))
+            .collect();
+        if prefixed.len() == 1 {
+            Ok(prefixed[0])
+        } else {
+            Err(t!("player.unknown_room", room = rname))
+        }
     }
 
-    /// Find one of the neighbors of the current room based on its name. Case insensitive.
-    fn find_room(&self, rname: String) -> Result<Rc<RefCell<Room>>, ()> {
-	let room = self.location.borrow();
+    /// Move the player into `room`, springing any hazards there and using up
+    /// its curios.
+    fn enter(&mut self, board: &mut Board, room: RoomId, events: &mut Vec<Event>) {
+        self.location = room;
+        self.stats.visit(room);
+        if board.room(room).wumpus {
+            events.push(Event::EatenByWumpus);
+            self.kill();
+            return;
+        }
+        if board.room(room).pit {
+            events.push(Event::FellInPit);
+            self.kill();
+            return;
+        }
+        if board.room(room).bats {
+            let rooms: Vec<RoomId> = board.room_ids().into_iter().filter(|&r| !board.room(r).bats).collect();
+            if !rooms.is_empty() {
+                let dest = rooms[board.rng().gen_range(0, rooms.len())];
+                board.room_mut(room).visits += 1;
+                events.push(Event::CarriedByBats(board.room(dest).name.clone()));
+                self.enter(board, dest, events);
+                return;
+            }
+        }
+        let first_visit = board.room(room).visits == 0;
+        let curios = board.room_mut(room).enter();
+        if first_visit {
+            if let Some(ref text) = board.room(room).first_visit {
+                events.push(Event::Message(text.clone()));
+            }
+        }
+        let teleport = self.use_curios(board, room, curios, events);
+        trigger::on_enter(board, self, room, events);
+        for monster in &board.room(room).monsters {
+            events.push(Event::MonsterFound(monster.name.clone()));
+        }
+        if let Some(dest) = teleport {
+            if self.hp > 0 {
+                self.enter(board, dest, events);
+            }
+        }
+    }
 
-	for h in &room.halls {
-	    let o = h.other(&room);
-	    if o.borrow().name.to_lowercase() == rname {
-		return Ok(o.clone());
-	    }
-	}
-	Err(())
+    /// Use up `curios` found in `room`, putting back any left behind.
+    /// Returns where a teleporter among them sends the player.
+    fn use_curios(&mut self, board: &mut Board, room: RoomId, curios: Vec<Curio>, events: &mut Vec<Event>)
+                  -> Option<RoomId> {
+        let (left_behind, teleport) = {
+            let mut finder = Finder::new(self, board, events);
+            for c in curios {
+                finder.use_curio(c);
+            }
+            (finder.left_behind, finder.teleport)
+        };
+        board.room_mut(room).contents.extend(left_behind);
+        teleport
+    }
+
+    /// Pick up `curios` outside of entering a room, following a teleporter
+    /// among them at once.
+    fn take_curios(&mut self, board: &mut Board, room: RoomId, curios: Vec<Curio>, events: &mut Vec<Event>) {
+        if let Some(dest) = self.use_curios(board, room, curios, events) {
+            if self.hp > 0 {
+                self.previous = Some(self.location);
+                self.enter(board, dest, events);
+            }
+        }
+    }
+
+    /// Move the wumpus to a random room adjacent to its lair. If it wanders
+    /// into the player's room, the player is eaten.
+    fn startle_wumpus(&mut self, board: &mut Board, events: &mut Vec<Event>) {
+        let lair = match board.wumpus_location() {
+            Some(lair) => lair,
+            None => return,
+        };
+        let exits = board.neighbors(lair).to_vec();
+        let dest = match board.rng().choose(&exits) {
+            Some(&dest) => dest,
+            None => return,
+        };
+        board.room_mut(lair).wumpus = false;
+        board.room_mut(dest).wumpus = true;
+        if dest == self.location {
+            events.push(Event::WumpusAttacked);
+            self.kill();
+        }
+    }
+
+    /// Find one of the neighbors of the current room. `rname` is a case
+    /// insensitive name or unique prefix of one, the number of an exit
+    /// counting from 1, a direction from `DIRECTIONS` or `back`. If nothing
+    /// matches, the error suggests the exits the player may have meant.
+    pub fn find_room(&self, board: &Board, rname: &str) -> Result<RoomId> {
+        let exits = board.neighbors(self.location);
+        if let Ok(n) = rname.parse::<usize>() {
+            return match exits.get(n.wrapping_sub(1)) {
+                Some(&room) => Ok(room),
+                None => Err(t!("player.no_exit", number = n)),
+            };
+        }
+        if rname == "back" {
+            return match self.previous {
+                Some(room) if exits.contains(&room) => Ok(room),
+                _ => Err(t!("player.no_way_back")),
+            };
+        }
+        if let Some(room) = board.find_neighbor(self.location, rname) {
+            return Ok(room);
+        }
+        if let Some(i) = DIRECTIONS.iter().position(|&d| d == rname) {
+            return match exits.get(i) {
+                Some(&room) => Ok(room),
+                None => Err(t!("player.no_exit", number = i + 1)),
+            };
+        }
+
+        let rname = rname.to_lowercase();
+        let prefixed: Vec<RoomId> = exits.iter().cloned()
+            .filter(|&r| board.room(r).name.to_lowercase().starts_with(&rname))
+            .collect();
+        if prefixed.len() == 1 {
+            return Ok(prefixed[0]);
+        }
+        let suggestions: Vec<&str> = if prefixed.is_empty() {
+            exits.iter()
+                .map(|&r| &board.room(r).name[..])
+                .filter(|name| edit_distance(&name.to_lowercase(), &rname) <= 2)
+                .collect()
+        } else {
+            prefixed.iter().map(|&r| &board.room(r).name[..]).collect()
+        };
+        if suggestions.is_empty() {
+            Err(t!("player.no_hall", room = rname))
+        } else {
+            Err(t!("player.no_hall_suggest", room = rname, rooms = suggestions.join(&t!("player.or"))))
+        }
+    }
+
+    /// The player's condition.
+    pub fn status(&self) -> String {
+        let mut status = t!("player.status", hp = self.hp, gold = self.gold, arrows = self.arrows);
+        if !self.items.is_empty() {
+            let items: Vec<String> = self.items.iter().map(|(item, n)| format!("{} x{}", item, n)).collect();
+            status.push(' ');
+            status.push_str(&t!("player.carrying", items = items.join(", ")));
+        }
+        status
     }
 }
 
-/**/impl fmt::Display for Player {
-/**/    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-/**/        write!(f, "You find yourself in {}.\n\nYou have {} HP and {} gold.",
-/**/               self.location.borrow().name, self.hp, self.gold)
-/**/    }
-/**/}
+/// The number of single character insertions, deletions and substitutions
+/// needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..b.len() + 1).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let sub = prev[j] + if ca == cb { 0 } else { 1 };
+            cur.push(std::cmp::min(sub, std::cmp::min(prev[j + 1], cur[j]) + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Command, Player};
+    use super::super::board::Board;
+    use super::super::curio::{Curio, Key, LockedChest, Potion, Teleporter};
+    use super::super::difficulty::Difficulty;
+    use super::super::event::Event;
+    use super::super::room::Room;
+
+    #[test]
+    fn shoot_wumpus() {
+        let mut board = Board::new();
+        let entry = board.add_room(Room::new("Entry", Vec::new(), false));
+        let lair = board.add_room(Room::new("Lair", Vec::new(), true));
+        board.add_hall(entry, lair);
+
+        let mut player = Player::new(entry);
+        assert!(player.act(&mut board, Command::Shoot("nowhere".to_string())).is_err());
+        assert_eq!(player.act(&mut board, Command::Shoot("lair".to_string())), Ok(vec![Event::WumpusKilled]));
+        assert!(player.won);
+        assert_eq!(player.arrows, 4);
+        assert_eq!(board.wumpus_location(), None);
+    }
+
+    #[test]
+    fn find_room_aliases() {
+        let mut board = Board::new();
+        let entry = board.add_room(Room::new("Entry", Vec::new(), false));
+        let hall = board.add_room(Room::new("Great Hall", Vec::new(), false));
+        let gallery = board.add_room(Room::new("Gallery", Vec::new(), false));
+        let library = board.add_room(Room::new("Library", Vec::new(), false));
+        board.add_hall(entry, hall);
+        board.add_hall(entry, gallery);
+        board.add_hall(entry, library);
+
+        let mut player = Player::new(entry);
+        assert_eq!(player.find_room(&board, "great hall"), Ok(hall));
+        assert_eq!(player.find_room(&board, "gr"), Ok(hall));
+        assert_eq!(player.find_room(&board, "3"), Ok(library));
+        assert_eq!(player.find_room(&board, "east"), Ok(gallery));
+        assert_eq!(player.find_room(&board, "west"), Err("There is no exit number 4.".to_string()));
+        assert!(player.find_room(&board, "4").is_err());
+        assert!(player.find_room(&board, "back").is_err());
+        assert_eq!(player.find_room(&board, "g"),
+                   Err("There is no hall to g. Did you mean Great Hall or Gallery?".to_string()));
+        assert_eq!(player.find_room(&board, "libary"),
+                   Err("There is no hall to libary. Did you mean Library?".to_string()));
+        assert_eq!(player.find_room(&board, "kitchen"), Err("There is no hall to kitchen.".to_string()));
+
+        player.act(&mut board, Command::Go("library".to_string())).unwrap();
+        assert_eq!(player.find_room(&board, "back"), Ok(entry));
+    }
+
+    #[test]
+    fn fall_in_pit() {
+        let mut board = Board::new();
+        let entry = board.add_room(Room::new("Entry", Vec::new(), false));
+        let pit = board.add_room(Room::new("Pit", Vec::new(), false));
+        board.room_mut(pit).pit = true;
+        board.add_hall(entry, pit);
+
+        let mut player = Player::new(entry);
+        assert_eq!(player.act(&mut board, Command::Go("pit".to_string())), Ok(vec![Event::FellInPit]));
+        assert_eq!(player.location, pit);
+        assert!(player.hp <= 0);
+    }
+
+    #[test]
+    fn keys_potions_and_teleporters() {
+        let mut board = Board::new();
+        let entry = board.add_room(Room::new("Entry", Vec::new(), false));
+        let vault = board.add_room(Room::new("Vault", vec![Curio::new(LockedChest(80))], false));
+        let closet = board.add_room(Room::new("Closet", vec![Curio::new(Key), Curio::new(Potion(5))], false));
+        let portal = board.add_room(Room::new("Portal", vec![Curio::new(Teleporter(Some("vault".to_string())))], false));
+        board.add_hall(entry, vault);
+        board.add_hall(entry, closet);
+        board.add_hall(closet, portal);
+
+        let mut player = Player::new(entry);
+        assert_eq!(player.act(&mut board, Command::Go("vault".to_string())), Ok(vec![Event::ChestLocked]));
+        assert_eq!(board.room(vault).contents.len(), 1);
+
+        player.act(&mut board, Command::Go("entry".to_string())).unwrap();
+        assert_eq!(player.act(&mut board, Command::Go("closet".to_string())), Ok(vec![Event::Key, Event::Potion(5)]));
+        assert_eq!((player.hp, player.max_hp), (30, 30));
+        assert_eq!(player.status(), "You have 30 HP, 0 gold and 5 arrows. You carry: key x1.");
+
+        assert_eq!(player.act(&mut board, Command::Go("portal".to_string())),
+                   Ok(vec![Event::Teleported("Vault".to_string()), Event::LockedChest(80)]));
+        assert_eq!(player.location, vault);
+        assert_eq!(player.gold, 80);
+        assert!(player.items.is_empty());
+        assert!(board.room(vault).contents.is_empty());
+    }
+
+    #[test]
+    fn travel_between_known_rooms() {
+        let mut board = Board::new();
+        let entry = board.add_room(Room::new("Entry", Vec::new(), false));
+        let hall = board.add_room(Room::new("Hall", Vec::new(), false));
+        let gallery = board.add_room(Room::new("Gallery", Vec::new(), false));
+        let crypt = board.add_room(Room::new("Crypt", Vec::new(), false));
+        let pit = board.add_room(Room::new("Pit", Vec::new(), false));
+        board.room_mut(pit).pit = true;
+        board.add_hall(entry, hall);
+        board.add_hall(hall, gallery);
+        board.add_hall(gallery, crypt);
+        board.add_hall(gallery, pit);
+
+        let mut player = Player::new(entry);
+        player.stats.visit(entry);
+        assert!(player.act(&mut board, Command::Travel("gallery".to_string())).is_err());
+        for room in &["hall", "gallery", "crypt"] {
+            player.act(&mut board, Command::Go(room.to_string())).unwrap();
+        }
+
+        // The draft in the gallery stops the player on the way back.
+        assert_eq!(player.act(&mut board, Command::Travel("entry".to_string())),
+                   Ok(vec![Event::Walked("Gallery".to_string()), Event::TravelStopped("Entry".to_string())]));
+        assert_eq!(player.location, gallery);
+        assert_eq!(player.act(&mut board, Command::Travel("en".to_string())),
+                   Ok(vec![Event::Walked("Hall".to_string()), Event::Walked("Entry".to_string())]));
+        assert_eq!((player.location, player.previous), (entry, Some(hall)));
+        assert_eq!(player.stats.turns, 6);
+
+        assert!(player.act(&mut board, Command::Travel("entry".to_string())).is_err());
+        assert!(player.act(&mut board, Command::Travel("pit".to_string())).is_err());
+    }
+
+    #[test]
+    fn travel_only_to_rooms_you_visited() {
+        let mut board = Board::new();
+        let entry = board.add_room(Room::new("Entry", Vec::new(), false));
+        let hall = board.add_room(Room::new("Hall", Vec::new(), false));
+        let gallery = board.add_room(Room::new("Gallery", Vec::new(), false));
+        board.add_hall(entry, hall);
+        board.add_hall(hall, gallery);
+
+        let (mut alice, _) = Player::spawn(&mut board, Difficulty::default());
+        let (mut bob, _) = Player::spawn(&mut board, Difficulty::default());
+        alice.act(&mut board, Command::Go("hall".to_string())).unwrap();
+        alice.act(&mut board, Command::Go("gallery".to_string())).unwrap();
+
+        assert!(bob.find_known_room(&board, "hall").is_err());
+        assert!(bob.act(&mut board, Command::Travel("gallery".to_string())).is_err());
+        assert_eq!(bob.location, entry);
+        assert_eq!(alice.act(&mut board, Command::Travel("entry".to_string())),
+                   Ok(vec![Event::Walked("Hall".to_string()), Event::Walked("Entry".to_string())]));
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use rustc_serialize::json::{Json, ToJson};
+
+use super::board::{Board, Result};
+use super::difficulty::Difficulty;
+use super::event::Event;
+use super::player::{Command, Player};
+use super::room::RoomId;
+use lang::{self, Lang};
+
+/// A recorded game: the level it was played on, the seed of the board's RNG,
+/// how the player started out and every command they gave, along with what
+/// each one printed.
+/// Playing the commands back on the same level with the same seed gives the
+/// same game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    /// The name of the level file. Replays are kept next to their level, so
+    /// this is relative to the replay file.
+    pub level: String,
+    pub level_hash: u64,
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    /// The language the game was played in, which the output is written in.
+    pub lang: Lang,
+    /// The gold, HP and maximum HP the player started with, which a campaign
+    /// carries over from the level before.
+    pub gold: i32,
+    pub hp: i32,
+    pub max_hp: i32,
+    /// What happened as the player arrived.
+    pub start: Vec<String>,
+    pub commands: Vec<Command>,
+    /// What each command printed: its events, or the error it failed with.
+    pub output: Vec<Vec<String>>,
+}
+
+/// A hash of a level file's contents, to tell when a level has changed under
+/// a replay. This is 64-bit FNV-1a, which unlike `DefaultHasher` is the same
+/// on every build.
+pub fn level_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+fn lines(events: &[Event]) -> Vec<String> {
+    events.iter().map(|e| e.to_string()).collect()
+}
+
+impl Replay {
+    /// Start recording a game on the level at `level`, whose contents hash to
+    /// `level_hash`, on a board seeded with `seed`. `player` is the player as
+    /// they were before arriving, and `start` what happened as they arrived.
+    pub fn new(level: &Path, level_hash: u64, seed: u64, player: &Player, start: &[Event]) -> Replay {
+        Replay {
+            level: level.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default(),
+            level_hash,
+            seed,
+            difficulty: player.difficulty,
+            lang: lang::current(),
+            gold: player.gold,
+            hp: player.hp,
+            max_hp: player.max_hp,
+            start: lines(start),
+            commands: Vec::new(),
+            output: Vec::new(),
+        }
+    }
+
+    /// The player as they were before arriving.
+    fn player(&self) -> Player {
+        let mut player = Player::with_difficulty(RoomId(0), self.difficulty);
+        player.gold = self.gold;
+        player.hp = self.hp;
+        player.max_hp = self.max_hp;
+        player
+    }
+
+    /// Where the `n`th replay of a game on `level` with `seed` is kept: next
+    /// to the level, with `castle.json` getting `castle.<seed>.replay.json`,
+    /// then `castle.<seed>.2.replay.json` and so on.
+    pub fn path_for(level: &Path, seed: u64, n: u32) -> PathBuf {
+        let stem = level.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        if n == 1 {
+            level.with_file_name(format!("{}.{}.replay.json", stem, seed))
+        } else {
+            level.with_file_name(format!("{}.{}.{}.replay.json", stem, seed, n))
+        }
+    }
+
+    /// The level file of the replay kept at `path`.
+    pub fn level_path(&self, path: &Path) -> PathBuf {
+        path.with_file_name(&self.level)
+    }
+
+    pub fn record(&mut self, cmd: &Command, result: &Result<Vec<Event>>) {
+        self.commands.push(cmd.clone());
+        self.output.push(match *result {
+            Ok(ref events) => lines(events),
+            Err(ref e) => vec![e.clone()],
+        });
+    }
+
+    /// Play the commands back on `level`, recording the game afresh.
+    pub fn play(&self, level: &Json) -> Result<Replay> {
+        let mut board = Board::from_json_seeded(level, self.seed)?;
+        let before = self.player();
+        let (mut player, events) = before.next_level(&mut board);
+        let mut replay = Replay::new(Path::new(&self.level), self.level_hash, self.seed, &before, &events);
+        for cmd in &self.commands {
+            let result = player.act(&mut board, cmd.clone());
+            replay.record(cmd, &result);
+        }
+        Ok(replay)
+    }
+
+    /// Describe the first place `other` went differently, if it did.
+    pub fn diff(&self, other: &Replay) -> Option<String> {
+        if self.start != other.start {
+            return Some(format!("The game started differently: {:?} instead of {:?}", other.start, self.start));
+        }
+        for (i, (a, b)) in self.output.iter().zip(&other.output).enumerate() {
+            if a != b {
+                return Some(format!("Command {} ({}) gave {:?} instead of {:?}", i + 1, self.commands[i], b, a));
+            }
+        }
+        if self.output.len() != other.output.len() {
+            return Some(format!("{} commands were played instead of {}", other.output.len(), self.output.len()));
+        }
+        None
+    }
+
+    pub fn from_json(json: &Json) -> Result<Replay> {
+        let string = |key: &str| json.find(key).and_then(|v| v.as_string()).ok_or(format!("Unable to parse {}", key));
+        let level = string("level")?.to_string();
+        let level_hash = u64::from_str_radix(string("level_hash")?, 16).map_err(|_| "Unable to parse level_hash".to_string())?;
+        let seed = json.find("seed").and_then(|s| s.as_u64()).ok_or("Unable to parse seed".to_string())?;
+        let difficulty = Difficulty::from_name(string("difficulty")?).ok_or("Unable to parse difficulty".to_string())?;
+        let int = |key: &str| json.find(key).and_then(|v| v.as_i64()).map(|v| v as i32).ok_or(format!("Unable to parse {}", key));
+        let (gold, hp, max_hp) = (int("gold")?, int("hp")?, int("max_hp")?);
+        let lang = Lang::from_code(string("lang")?).ok_or("Unable to parse lang".to_string())?;
+        let start = parse_lines(json.find("start").ok_or("Unable to parse start".to_string())?)?;
+
+        let mut replay = Replay {
+            level, level_hash, seed, difficulty, lang, gold, hp, max_hp, start, commands: Vec::new(), output: Vec::new(),
+        };
+        let turns = json.find("turns").and_then(|t| t.as_array()).ok_or("Unable to parse turns".to_string())?;
+        for t in turns {
+            let cmd = t.find("command").ok_or("Unable to parse command".to_string())?;
+            replay.commands.push(parse_command(cmd)?);
+            replay.output.push(parse_lines(t.find("output").ok_or("Unable to parse output".to_string())?)?);
+        }
+        Ok(replay)
+    }
+
+    pub fn load(path: &Path) -> Result<Replay> {
+        let mut file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+        let json = Json::from_reader(&mut file).map_err(|_| "Unable to create JSON reader".to_string())?;
+        Replay::from_json(&json)
+    }
+
+    /// Save the replay next to `level` under the first name from `path_for`
+    /// that no file has yet, and return the path it was saved to.
+    pub fn save_next_to(&self, level: &Path) -> Result<PathBuf> {
+        for n in 1.. {
+            let path = Replay::path_for(level, self.seed, n);
+            let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => file,
+                Err(ref e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(format!("Unable to create {}: {}", path.display(), e)),
+            };
+            write!(file, "{}", self.to_json().pretty()).map_err(|e| format!("Unable to write {}: {}", path.display(), e))?;
+            return Ok(path);
+        }
+        unreachable!()
+    }
+}
+
+impl ToJson for Replay {
+    fn to_json(&self) -> Json {
+        let turns: Vec<Json> = self.commands.iter().zip(&self.output).map(|(cmd, output)| {
+            let mut obj = BTreeMap::new();
+            obj.insert("command".to_string(), command_json(cmd));
+            obj.insert("output".to_string(), output.to_json());
+            Json::Object(obj)
+        }).collect();
+
+        let mut obj = BTreeMap::new();
+        obj.insert("level".to_string(), self.level.to_json());
+        obj.insert("level_hash".to_string(), format!("{:016x}", self.level_hash).to_json());
+        obj.insert("seed".to_string(), self.seed.to_json());
+        obj.insert("difficulty".to_string(), self.difficulty.name.to_json());
+        obj.insert("lang".to_string(), self.lang.code().to_json());
+        obj.insert("gold".to_string(), self.gold.to_json());
+        obj.insert("hp".to_string(), self.hp.to_json());
+        obj.insert("max_hp".to_string(), self.max_hp.to_json());
+        obj.insert("start".to_string(), self.start.to_json());
+        obj.insert("turns".to_string(), Json::Array(turns));
+        Json::Object(obj)
+    }
+}
+
+/// Commands are written `{"go": "foyer"}`, `{"shoot": "2"}`,
+/// `{"travel": "entry"}`, `"fight"`, `"flee"` or `"wait"`.
+fn command_json(cmd: &Command) -> Json {
+    let (verb, target) = match *cmd {
+        Command::Go(ref room) => ("go", room),
+        Command::Shoot(ref room) => ("shoot", room),
+        Command::Travel(ref room) => ("travel", room),
+        Command::Fight => return "fight".to_json(),
+        Command::Flee => return "flee".to_json(),
+        Command::Wait => return "wait".to_json(),
+    };
+    let mut obj = BTreeMap::new();
+    obj.insert(verb.to_string(), target.to_json());
+    Json::Object(obj)
+}
+
+fn parse_command(json: &Json) -> Result<Command> {
+    match json.as_string() {
+        Some("fight") => return Ok(Command::Fight),
+        Some("flee") => return Ok(Command::Flee),
+        Some("wait") => return Ok(Command::Wait),
+        _ => {},
+    }
+    let target = |verb: &str| json.find(verb).and_then(|t| t.as_string()).map(|t| t.to_string());
+    if let Some(room) = target("go") {
+        Ok(Command::Go(room))
+    } else if let Some(room) = target("shoot") {
+        Ok(Command::Shoot(room))
+    } else if let Some(room) = target("travel") {
+        Ok(Command::Travel(room))
+    } else {
+        Err("Unable to parse command".to_string())
+    }
+}
+
+fn parse_lines(json: &Json) -> Result<Vec<String>> {
+    let list = json.as_array().ok_or("Unable to parse output".to_string())?;
+    list.iter()
+        .map(|l| l.as_string().map(|l| l.to_string()).ok_or("Unable to parse output".to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+    use std::path::Path;
+
+    use rustc_serialize::json::{Json, ToJson};
+
+    use super::{level_hash, Replay};
+    use super::super::board::Board;
+    use super::super::difficulty::{Difficulty, EASY};
+    use super::super::player::{Command, Player};
+    use super::super::room::RoomId;
+
+    const LEVEL: &str = r#"{"rooms": [{"name": "Entry", "curios": 2},
+                                       {"name": "Roost", "curios": 3, "bats": true},
+                                       {"name": "Hall", "curios": ["random", "random"]},
+                                       {"name": "Lair", "curios": 0, "wumpus": true}],
+                            "halls": [[0, 1], [0, 2], [2, 3]]}"#;
+
+    fn record(seed: u64, before: &Player, commands: &[Command]) -> Replay {
+        let level = Json::from_str(LEVEL).unwrap();
+        let mut board = Board::from_json_seeded(&level, seed).unwrap();
+        let (mut player, events) = before.next_level(&mut board);
+        let mut replay = Replay::new(Path::new("levels/level.json"), level_hash(LEVEL.as_bytes()), seed, before, &events);
+        for cmd in commands {
+            let result = player.act(&mut board, cmd.clone());
+            replay.record(cmd, &result);
+        }
+        replay
+    }
+
+    #[test]
+    fn playback_matches_recording() {
+        let commands = vec![Command::Go("roost".to_string()), Command::Wait, Command::Shoot("1".to_string()),
+                            Command::Go("nowhere".to_string()), Command::Go("1".to_string())];
+        let level = Json::from_str(LEVEL).unwrap();
+        // A fresh player, and one carrying gold and HP over on easy.
+        let fresh = Player::new(RoomId(0));
+        let mut carried = Player::with_difficulty(RoomId(0), EASY);
+        carried.gold = 40;
+        carried.hp = 9;
+        for seed in 0..20 {
+            let before = if seed % 2 == 0 { &fresh } else { &carried };
+            let replay = record(seed, before, &commands);
+            let played = replay.play(&level).unwrap();
+            assert_eq!(replay.diff(&played), None);
+            assert_eq!(played, replay);
+        }
+    }
+
+    #[test]
+    fn json_round_trip_and_diff() {
+        let replay = record(3, &Player::with_difficulty(RoomId(0), EASY), &[Command::Go("hall".to_string()), Command::Wait]);
+        let loaded = Replay::from_json(&Json::from_str(&replay.to_json().to_string()).unwrap()).unwrap();
+        assert_eq!(loaded, replay);
+        assert_eq!(loaded.difficulty, EASY);
+        assert!(loaded.difficulty != Difficulty::default());
+
+        let mut json = replay.to_json();
+        json.as_object_mut().unwrap().remove("lang");
+        assert_eq!(Replay::from_json(&json), Err("Unable to parse lang".to_string()));
+
+        let mut tampered = replay.clone();
+        tampered.output[1] = vec!["Nothing at all.".to_string()];
+        assert!(replay.diff(&tampered).unwrap().starts_with("Command 2 (wait)"));
+        tampered.output.pop();
+        assert!(replay.diff(&tampered).is_some());
+        assert_ne!(level_hash(b"a"), level_hash(b"b"));
+    }
+
+    #[test]
+    fn every_save_gets_its_own_file() {
+        let dir = env::temp_dir().join(format!("hw05-replays-{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let level = dir.join("castle.json");
+        let replay = record(7, &Player::new(RoomId(0)), &[Command::Wait]);
+        assert_eq!(replay.level, "level.json");
+
+        let first = replay.save_next_to(&level).unwrap();
+        let second = replay.save_next_to(&level).unwrap();
+        assert_eq!(first, dir.join("castle.7.replay.json"));
+        assert_eq!(second, dir.join("castle.7.2.replay.json"));
+        assert_eq!(Replay::load(&first).unwrap(), replay);
+        assert_eq!(replay.level_path(&second), dir.join("level.json"));
+        assert_eq!(replay.level_path(Path::new("castle.7.replay.json")), Path::new("level.json"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
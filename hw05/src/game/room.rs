@@ -1,16 +1,47 @@
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::fmt;
 use std::mem;
 
 use super::curio::Curio;
-use super::hall::Hall;
+use super::monster::Monster;
+use super::trigger::Trigger;
 
-#[derive(Debug)]
+/// Index of a room in its `Board`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RoomId(pub usize);
+
+impl fmt::Display for RoomId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Room {
     pub name: String,
+    /// Text shown whenever the player looks around the room.
+    pub description: Option<String>,
+    /// Text shown the first time the player enters the room.
+    pub first_visit: Option<String>,
+    pub triggers: Vec<Trigger>,
     pub contents: Vec<Curio>,
-    pub halls: Vec<Rc<Hall>>,
+    /// The monsters in the room right now.
+    pub monsters: Vec<Monster>,
+    /// The rooms at the other end of this room's halls.
+    exits: Vec<RoomId>,
     pub wumpus: bool,
+    /// A bottomless pit that swallows anyone who enters.
+    pub pit: bool,
+    /// Super bats that carry anyone who enters to a random room.
+    pub bats: bool,
+    /// Number of times the player has entered this room.
+    pub visits: u32,
+    /// If set, the room's original curios come back this many visits after
+    /// they were last taken.
+    pub respawn: Option<u32>,
+    /// The curios the room restocks with.
+    pub spawn: Vec<Curio>,
+    /// The visit on which the room's curios were last taken.
+    pub looted_at: u32,
 }
 
 impl PartialEq for Room {
@@ -23,20 +54,75 @@ impl Eq for Room {}
 
 impl Room {
     pub fn new(name: &str, contents: Vec<Curio>, wumpus: bool) -> Self {
-	Room { name: name.to_string(), contents: contents, halls: Vec::new(), wumpus: wumpus }
+	Room {
+	    name: name.to_string(),
+	    description: None,
+	    first_visit: None,
+	    triggers: Vec::new(),
+	    spawn: contents.clone(),
+	    contents,
+	    monsters: Vec::new(),
+	    exits: Vec::new(),
+	    wumpus,
+	    pit: false,
+	    bats: false,
+	    visits: 0,
+	    respawn: None,
+	    looted_at: 0,
+	}
+    }
+
+    /// Record a visit to the room and take all of its curios, restocking them
+    /// first if the room respawns and enough visits have passed.
+    pub fn enter(&mut self) -> Vec<Curio> {
+	self.visits += 1;
+	if let Some(n) = self.respawn {
+	    if self.contents.is_empty() && self.visits.saturating_sub(self.looted_at) >= n {
+		self.contents = self.spawn.clone();
+	    }
+	}
+	if !self.contents.is_empty() {
+	    self.looted_at = self.visits;
+	}
+	mem::take(&mut self.contents)
+    }
+
+    pub fn exits(&self) -> &[RoomId] {
+	&self.exits
+    }
+
+    pub(super) fn add_exit(&mut self, to: RoomId) {
+	self.exits.push(to);
     }
+}
 
-    pub fn add_hall(&mut self, hall: Rc<Hall>) {
-	self.halls.push(hall);
+#[cfg(test)]
+mod test {
+    use super::Room;
+    use super::super::curio::{Chest, Curio, SpikeTrap};
+
+    #[test]
+    fn curios_consumed_on_first_visit() {
+	let mut room = Room::new("Vault", vec![Curio::new(Chest(10)), Curio::new(SpikeTrap(2))], false);
+	assert_eq!(room.enter().len(), 2);
+	assert_eq!(room.enter().len(), 0);
+	assert_eq!(room.visits, 2);
     }
 
-    pub fn neighbors_string(&self) -> String {
-	let mut vec: Vec<String> = Vec::new();
-	for h in &self.halls {
-	    let o = h.other(&self);
-	    let s: String = o.borrow().name.clone();
-	    vec.push(s);
+    #[test]
+    fn curios_respawn() {
+	let mut room = Room::new("Vault", vec![Curio::new(Chest(10))], false);
+	room.respawn = Some(2);
+	assert_eq!(room.enter().len(), 1);
+	assert_eq!(room.enter().len(), 0);
+	assert_eq!(room.enter().len(), 1);
+	assert_eq!(room.enter().len(), 0);
+	assert_eq!(room.enter().len(), 1);
+
+	let mut room = Room::new("Larder", vec![Curio::new(Chest(10))], false);
+	room.respawn = Some(1);
+	for _ in 0..3 {
+	    assert_eq!(room.enter().len(), 1);
 	}
-	vec.join(" ")
     }
 }
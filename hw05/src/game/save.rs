@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+use rustc_serialize::json::{Json, ToJson};
+
+use super::board::{Board, Result};
+use super::difficulty::Difficulty;
+use super::player::Player;
+use super::room::RoomId;
+
+/// Write the board and the player's state to `writer`. Rooms are referred to
+/// by their index in `board.rooms`.
+pub fn save(board: &Board, player: &Player, writer: &mut dyn Write) -> Result<()> {
+    if player.location.0 >= board.len() {
+        return Err("Player is not on the board".to_string());
+    }
+
+    let mut json_player = BTreeMap::new();
+    json_player.insert("location".to_string(), player.location.0.to_json());
+    json_player.insert("previous".to_string(), player.previous.map(|r| r.0).to_json());
+    json_player.insert("hp".to_string(), player.hp.to_json());
+    json_player.insert("gold".to_string(), player.gold.to_json());
+    json_player.insert("arrows".to_string(), player.arrows.to_json());
+    json_player.insert("max_hp".to_string(), player.max_hp.to_json());
+    json_player.insert("difficulty".to_string(), player.difficulty.name.to_json());
+    json_player.insert("items".to_string(), player.items.to_json());
+
+    let stats = &player.stats;
+    let mut json_stats = BTreeMap::new();
+    json_stats.insert("turns".to_string(), stats.turns.to_json());
+    json_stats.insert("damage_taken".to_string(), stats.damage_taken.to_json());
+    json_stats.insert("food_eaten".to_string(), stats.food_eaten.to_json());
+    json_stats.insert("hp_healed".to_string(), stats.hp_healed.to_json());
+    json_stats.insert("wumpus_kills".to_string(), stats.wumpus_kills.to_json());
+    json_stats.insert("monster_kills".to_string(), stats.monster_kills.to_json());
+    let visited: Vec<usize> = stats.rooms_visited.iter().map(|r| r.0).collect();
+    json_stats.insert("rooms_visited".to_string(), visited.to_json());
+    json_player.insert("stats".to_string(), Json::Object(json_stats));
+
+    let mut json = BTreeMap::new();
+    json.insert("board".to_string(), board.to_json());
+    json.insert("player".to_string(), Json::Object(json_player));
+
+    write!(writer, "{}", Json::Object(json).pretty()).map_err(|e| format!("Unable to write save: {}", e))
+}
+
+/// Restore a board and player written by `save`.
+pub fn load(reader: &mut dyn Read) -> Result<(Board, Player)> {
+    let json: Json = Json::from_reader(reader).map_err(|_| "Unable to create JSON reader".to_string())?;
+
+    let json_board: &Json = json.find("board").ok_or("Unable to parse board".to_string())?;
+    let board = Board::from_json(json_board)?;
+    for r in board.room_ids() {
+        let room = board.room(r);
+        if room.looted_at > room.visits {
+            return Err(format!("Invalid visits to {}", room.name));
+        }
+    }
+
+    let json_player: &Json = json.find("player").ok_or("Unable to parse player".to_string())?;
+    let location: usize = parse_int(json_player, "location")? as usize;
+    if location >= board.len() {
+        return Err("Invalid player location".to_string());
+    }
+    let difficulty = json_player.find("difficulty")
+        .and_then(|name| name.as_string())
+        .and_then(Difficulty::from_name)
+        .ok_or("Unable to parse difficulty".to_string())?;
+    let mut player = Player::with_difficulty(RoomId(location), difficulty);
+    player.previous = match json_player.find("previous") {
+        Some(&Json::Null) => None,
+        Some(json_previous) => match json_previous.as_u64() {
+            Some(r) if (r as usize) < board.len() => Some(RoomId(r as usize)),
+            _ => return Err("Invalid previous room".to_string()),
+        },
+        None => return Err("Unable to parse previous".to_string()),
+    };
+    player.hp = parse_int(json_player, "hp")? as i32;
+    player.gold = parse_int(json_player, "gold")? as i32;
+    player.arrows = parse_int(json_player, "arrows")? as i32;
+    player.max_hp = parse_int(json_player, "max_hp")? as i32;
+    let items = json_player.find("items")
+        .and_then(|items| items.as_object())
+        .ok_or("Unable to parse items".to_string())?;
+    for (item, n) in items {
+        let n = n.as_u64().ok_or("Unable to parse items".to_string())?;
+        player.items.insert(item.clone(), n as u32);
+    }
+
+    let json_stats: &Json = json_player.find("stats").ok_or("Unable to parse stats".to_string())?;
+    let stats = &mut player.stats;
+    stats.turns = parse_int(json_stats, "turns")? as u32;
+    stats.damage_taken = parse_int(json_stats, "damage_taken")? as i32;
+    stats.food_eaten = parse_int(json_stats, "food_eaten")? as u32;
+    stats.hp_healed = parse_int(json_stats, "hp_healed")? as i32;
+    stats.wumpus_kills = parse_int(json_stats, "wumpus_kills")? as u32;
+    stats.monster_kills = parse_int(json_stats, "monster_kills")? as u32;
+    let visited = json_stats.find("rooms_visited")
+        .and_then(|v| v.as_array())
+        .ok_or("Unable to parse rooms_visited".to_string())?;
+    for r in visited {
+        let r = r.as_u64().ok_or("Unable to parse rooms_visited".to_string())? as usize;
+        if r >= board.len() {
+            return Err("Invalid visited room".to_string());
+        }
+        stats.visit(RoomId(r));
+    }
+
+    Ok((board, player))
+}
+
+fn parse_int(json: &Json, key: &str) -> Result<i64> {
+    let json_val: &Json = json.find(key).ok_or(format!("Unable to parse {}", key))?;
+    json_val.as_i64().ok_or(format!("Unable to parse {}", key))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{load, save};
+    use super::super::board::Board;
+    use super::super::difficulty::HARD;
+    use super::super::player::Player;
+    use super::super::room::RoomId;
+
+    #[test]
+    fn round_trip() {
+        let level = r#"{"rooms": [{"name": "Entry", "curios": [{"chest": 5}]},
+                                  {"name": "Lair", "curios": 0, "wumpus": true},
+                                  {"name": "Hole", "curios": [], "pit": true}],
+                        "halls": [[0, 1], [0, 2]]}"#;
+        let board = Board::build_board(&mut level.as_bytes()).unwrap();
+        let mut player = Player::with_difficulty(RoomId(2), HARD);
+        player.previous = Some(RoomId(0));
+        player.hp = 7;
+        player.gold = 42;
+        player.arrows = 3;
+        player.max_hp = 30;
+        player.add_item("key");
+        player.stats.turns = 12;
+        player.stats.damage_taken = 4;
+        player.stats.visit(RoomId(0));
+        player.stats.visit(RoomId(2));
+
+        let mut buf = Vec::new();
+        save(&board, &player, &mut buf).unwrap();
+        let (loaded, player) = load(&mut &buf[..]).unwrap();
+
+        assert_eq!(loaded.to_json(), board.to_json());
+        assert_eq!((player.location, player.previous), (RoomId(2), Some(RoomId(0))));
+        assert_eq!((player.hp, player.gold, player.arrows), (7, 42, 3));
+        assert_eq!(player.max_hp, 30);
+        assert_eq!(player.difficulty, HARD);
+        assert_eq!(player.items.get("key"), Some(&1));
+        assert_eq!(player.stats.turns, 12);
+        assert_eq!(player.stats.damage_taken, 4);
+        assert_eq!(player.stats.rooms_visited, vec![RoomId(0), RoomId(2)]);
+        assert_eq!(loaded.neighbors_string(RoomId(0)), "Lair Hole");
+    }
+
+    #[test]
+    fn reject_inconsistent_rooms() {
+        let level = r#"{"rooms": [{"name": "Entry", "curios": 0}], "halls": [], "respawn": 2}"#;
+        let mut board = Board::build_board(&mut level.as_bytes()).unwrap();
+        board.room_mut(RoomId(0)).visits = 1;
+        board.room_mut(RoomId(0)).looted_at = 3;
+
+        let mut buf = Vec::new();
+        save(&board, &Player::new(RoomId(0)), &mut buf).unwrap();
+        assert_eq!(load(&mut &buf[..]).err(), Some("Invalid visits to Entry".to_string()));
+    }
+
+    #[test]
+    fn reject_missing_fields() {
+        let level = r#"{"rooms": [{"name": "Entry", "curios": 0}], "halls": []}"#;
+        let board = Board::build_board(&mut level.as_bytes()).unwrap();
+        let mut buf = Vec::new();
+        save(&board, &Player::new(RoomId(0)), &mut buf).unwrap();
+        let save = String::from_utf8(buf).unwrap();
+
+        for field in &["\"max_hp\"", "\"items\"", "\"stats\"", "\"monster_kills\"", "\"difficulty\""] {
+            let broken = save.replace(field, "\"unknown\"");
+            assert!(load(&mut broken.as_bytes()).is_err(), "loaded a save without {}", field);
+        }
+    }
+}
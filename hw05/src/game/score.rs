@@ -0,0 +1,174 @@
+use std::cmp;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use rustc_serialize::json::{Json, ToJson};
+
+use super::board::Result;
+use super::player::Player;
+use super::room::RoomId;
+
+const GOLD_POINTS: i64 = 10;
+const KILL_POINTS: i64 = 1000;
+const MONSTER_POINTS: i64 = 100;
+const HP_POINTS: i64 = 20;
+const TURN_PENALTY: i64 = 2;
+const MAX_HIGH_SCORES: usize = 10;
+
+/// What happened over the course of one player's run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunStats {
+    pub turns: u32,
+    pub damage_taken: i32,
+    pub food_eaten: u32,
+    pub hp_healed: i32,
+    pub wumpus_kills: u32,
+    pub monster_kills: u32,
+    /// Every room the player has entered, in the order of their first visit.
+    pub rooms_visited: Vec<RoomId>,
+}
+
+impl RunStats {
+    pub fn visit(&mut self, room: RoomId) {
+        if !self.rooms_visited.contains(&room) {
+            self.rooms_visited.push(room);
+        }
+    }
+}
+
+/// Points for a run: gold and kills count for you, turns against you,
+/// and HP left over only counts if you won.
+pub fn score(player: &Player) -> i64 {
+    let stats = &player.stats;
+    let mut score = player.gold as i64 * GOLD_POINTS
+        + stats.wumpus_kills as i64 * KILL_POINTS
+        + stats.monster_kills as i64 * MONSTER_POINTS
+        - stats.turns as i64 * TURN_PENALTY;
+    if player.won {
+        score += player.hp as i64 * HP_POINTS;
+    }
+    cmp::max(score, 0)
+}
+
+/// One line of a high-score table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighScore {
+    pub name: String,
+    pub score: i64,
+    pub won: bool,
+    pub turns: u32,
+}
+
+/// The best runs on one level, highest score first.
+#[derive(Debug, Default)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Where the high scores for the level at `level` are kept: next to it,
+    /// with `castle.json` getting `castle.scores.json`.
+    pub fn path_for(level: &Path) -> PathBuf {
+        let stem = level.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        level.with_file_name(format!("{}.scores.json", stem))
+    }
+
+    /// Read a high-score table. A missing file is an empty table.
+    pub fn load(path: &Path) -> Result<HighScores> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(HighScores::default()),
+            Err(e) => return Err(format!("Unable to open {}: {}", path.display(), e)),
+        };
+        let json = Json::from_reader(&mut file).map_err(|_| "Unable to create JSON reader".to_string())?;
+        let list = json.as_array().ok_or("Unable to parse high scores".to_string())?;
+        let mut entries = Vec::new();
+        for e in list {
+            let name = e.find("name").and_then(|n| n.as_string()).ok_or("Unable to parse name".to_string())?;
+            let score = e.find("score").and_then(|s| s.as_i64()).ok_or("Unable to parse score".to_string())?;
+            let won = e.find("won").and_then(|w| w.as_boolean()).ok_or("Unable to parse won".to_string())?;
+            let turns = e.find("turns").and_then(|t| t.as_u64()).ok_or("Unable to parse turns".to_string())?;
+            entries.push(HighScore { name: name.to_string(), score, won, turns: turns as u32 });
+        }
+        Ok(HighScores { entries })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let list: Vec<Json> = self.entries.iter().map(|e| {
+            let mut obj = BTreeMap::new();
+            obj.insert("name".to_string(), e.name.to_json());
+            obj.insert("score".to_string(), e.score.to_json());
+            obj.insert("won".to_string(), e.won.to_json());
+            obj.insert("turns".to_string(), e.turns.to_json());
+            Json::Object(obj)
+        }).collect();
+        let mut file = File::create(path).map_err(|e| format!("Unable to create {}: {}", path.display(), e))?;
+        write!(file, "{}", Json::Array(list).pretty()).map_err(|e| format!("Unable to write {}: {}", path.display(), e))
+    }
+
+    /// Add a run to the table, returning its rank counting from 1 if it made
+    /// the cut.
+    pub fn add(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self.entries.iter().position(|e| e.score < entry.score).unwrap_or(self.entries.len());
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(rank + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::path::Path;
+
+    use super::{score, HighScore, HighScores};
+    use super::super::player::Player;
+    use super::super::room::RoomId;
+
+    #[test]
+    fn scoring() {
+        let mut player = Player::new(RoomId(0));
+        player.gold = 30;
+        player.hp = 10;
+        player.stats.turns = 25;
+        player.stats.wumpus_kills = 1;
+        player.won = true;
+        assert_eq!(score(&player), 300 + 1000 + 200 - 50);
+
+        // Quitting with full HP earns nothing for it.
+        player.won = false;
+        assert_eq!(score(&player), 300 + 1000 - 50);
+        player.hp = 0;
+        assert_eq!(score(&player), 300 + 1000 - 50);
+        player.gold = 0;
+        player.stats.wumpus_kills = 0;
+        assert_eq!(score(&player), 0);
+    }
+
+    #[test]
+    fn high_score_table() {
+        assert_eq!(HighScores::path_for(Path::new("data/castle.json")), Path::new("data/castle.scores.json"));
+
+        let mut scores = HighScores::default();
+        let entry = |score| HighScore { name: "me".to_string(), score, won: false, turns: 3 };
+        for s in 0..10 {
+            assert!(scores.add(entry(s * 10)).is_some());
+        }
+        assert_eq!(scores.add(entry(-1)), None);
+        assert_eq!(scores.add(entry(55)), Some(5));
+        assert_eq!(scores.entries.len(), 10);
+        assert_eq!(scores.entries[0].score, 90);
+        assert_eq!(scores.entries[9].score, 10);
+
+        let path = env::temp_dir().join(format!("hw05-scores-{}.json", ::std::process::id()));
+        scores.save(&path).unwrap();
+        assert_eq!(HighScores::load(&path).unwrap().entries, scores.entries);
+        ::std::fs::remove_file(&path).unwrap();
+        assert!(HighScores::load(&path).unwrap().entries.is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+use super::board::{Board, Result};
+use super::event::Event;
+use super::player::Player;
+use super::room::RoomId;
+
+/// Something that must hold for a trigger to fire.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    Has(String),
+    Lacks(String),
+}
+
+/// Something a trigger does when it fires.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    Say(String),
+    Damage(i32),
+    Heal(i32),
+    Gold(i32),
+    Give(String),
+    Take(String),
+    /// Open a hall from the trigger's room to the named room.
+    OpenHall(String),
+}
+
+/// A scripted event in a level, written as a line of text, e.g.
+/// `on enter: if has key, take key, open hall to Treasure` or
+/// `on enter: say "A stone falls on your head.", damage 3 once`.
+///
+/// Conditions are `has ITEM` and `lacks ITEM`, joined with `and`. Actions
+/// are `say "TEXT"`, `damage N`, `heal N`, `gold N`, `give ITEM`,
+/// `take ITEM` and `open hall to ROOM`. A trailing `once` stops the trigger
+/// from firing again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trigger {
+    /// The text the trigger was parsed from, kept for saving.
+    pub source: String,
+    pub conditions: Vec<Condition>,
+    pub actions: Vec<Action>,
+    pub once: bool,
+    /// Whether the trigger has fired yet.
+    pub fired: bool,
+}
+
+impl Trigger {
+    pub fn parse(source: &str) -> Result<Trigger> {
+        let err = || format!("Unable to parse trigger {}", source);
+        let text = source.trim();
+        if !text.to_lowercase().starts_with("on enter:") {
+            return Err(format!("Triggers must start with \"on enter:\": {}", source));
+        }
+        let mut parts = split_outside_quotes(&text["on enter:".len()..]);
+
+        let mut conditions = Vec::new();
+        if parts.first().is_some_and(|p| p.to_lowercase().starts_with("if ")) {
+            let clause = parts.remove(0);
+            for cond in clause[3..].split(" and ") {
+                let (verb, item) = split_verb(cond);
+                match &verb[..] {
+                    "has" if !item.is_empty() => conditions.push(Condition::Has(item.to_lowercase())),
+                    "lacks" if !item.is_empty() => conditions.push(Condition::Lacks(item.to_lowercase())),
+                    _ => return Err(err()),
+                }
+            }
+        }
+
+        let mut once = false;
+        if parts.last().map(|p| p.eq_ignore_ascii_case("once")) == Some(true) {
+            parts.pop();
+            once = true;
+        } else if let Some(last) = parts.last_mut() {
+            if last.to_lowercase().ends_with(" once") {
+                let len = last.len() - " once".len();
+                last.truncate(len);
+                once = true;
+            }
+        }
+
+        let mut actions = Vec::new();
+        for part in parts {
+            let (verb, arg) = split_verb(&part);
+            let num = || arg.parse::<i32>().map_err(|_| err());
+            let action = match &verb[..] {
+                "say" if arg.len() >= 2 && arg.starts_with('"') && arg.ends_with('"') => {
+                    Action::Say(arg[1..arg.len() - 1].to_string())
+                },
+                "damage" => Action::Damage(num()?),
+                "heal" => Action::Heal(num()?),
+                "gold" => Action::Gold(num()?),
+                "give" if !arg.is_empty() => Action::Give(arg.to_lowercase()),
+                "take" if !arg.is_empty() => Action::Take(arg.to_lowercase()),
+                "open" if arg.to_lowercase().starts_with("hall to ") => {
+                    Action::OpenHall(arg["hall to ".len()..].trim().to_string())
+                },
+                _ => return Err(err()),
+            };
+            actions.push(action);
+        }
+        if actions.is_empty() {
+            return Err(err());
+        }
+
+        Ok(Trigger { source: source.to_string(), conditions, actions, once, fired: false })
+    }
+
+    /// Whether the trigger would fire for `player` now.
+    pub fn ready(&self, player: &Player) -> bool {
+        if self.once && self.fired {
+            return false;
+        }
+        self.conditions.iter().all(|c| match *c {
+            Condition::Has(ref item) => player.items.contains_key(item),
+            Condition::Lacks(ref item) => !player.items.contains_key(item),
+        })
+    }
+}
+
+/// Split a clause into its lowercased first word and the rest.
+fn split_verb(clause: &str) -> (String, &str) {
+    let clause = clause.trim();
+    match clause.find(' ') {
+        Some(i) => (clause[..i].to_lowercase(), clause[i + 1..].trim()),
+        None => (clause.to_lowercase(), ""),
+    }
+}
+
+/// Split on commas that are not inside double quotes.
+fn split_outside_quotes(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut cur = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c == ',' && !quoted {
+            parts.push(cur.trim().to_string());
+            cur.clear();
+        } else {
+            cur.push(c);
+        }
+    }
+    if !cur.trim().is_empty() {
+        parts.push(cur.trim().to_string());
+    }
+    parts
+}
+
+/// Fire the triggers of `room` that are ready now that `player` has entered
+/// it. Stops early if the player dies.
+pub fn on_enter(board: &mut Board, player: &mut Player, room: RoomId, events: &mut Vec<Event>) {
+    for i in 0..board.room(room).triggers.len() {
+        if player.hp <= 0 {
+            return;
+        }
+        if !board.room(room).triggers[i].ready(player) {
+            continue;
+        }
+        board.room_mut(room).triggers[i].fired = true;
+        let actions = board.room(room).triggers[i].actions.clone();
+        for action in actions {
+            run(board, player, room, action, events);
+        }
+    }
+}
+
+fn run(board: &mut Board, player: &mut Player, room: RoomId, action: Action, events: &mut Vec<Event>) {
+    match action {
+        Action::Say(text) => events.push(Event::Message(text)),
+        Action::Damage(dmg) => {
+            events.push(Event::Damage(dmg));
+            player.hurt(dmg);
+        },
+        Action::Heal(hp) => events.push(Event::Healed(player.heal(hp))),
+        Action::Gold(gold) => {
+            events.push(Event::Gold(gold));
+            player.gold += gold;
+        },
+        Action::Give(item) => {
+            player.add_item(&item);
+            events.push(Event::ItemGained(item));
+        },
+        Action::Take(item) => {
+            if player.take_item(&item) {
+                events.push(Event::ItemLost(item));
+            }
+        },
+        Action::OpenHall(name) => {
+            if let Some(to) = board.room_named(&name) {
+                if to != room && !board.neighbors(room).contains(&to) {
+                    board.add_hall(room, to);
+                    events.push(Event::HallOpened(board.room(to).name.clone()));
+                }
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Action, Condition, Trigger};
+
+    #[test]
+    fn parse_triggers() {
+        let t = Trigger::parse("on enter: if has key, take key, open hall to Great Hall").unwrap();
+        assert_eq!(t.conditions, vec![Condition::Has("key".to_string())]);
+        assert_eq!(t.actions, vec![Action::Take("key".to_string()), Action::OpenHall("Great Hall".to_string())]);
+        assert!(!t.once);
+
+        let t = Trigger::parse("On enter: damage 3 once").unwrap();
+        assert_eq!(t.actions, vec![Action::Damage(3)]);
+        assert!(t.once);
+
+        let t = Trigger::parse(r#"on enter: if lacks torch and has map, say "Dark, so dark.", once"#).unwrap();
+        assert_eq!(t.conditions.len(), 2);
+        assert_eq!(t.actions, vec![Action::Say("Dark, so dark.".to_string())]);
+        assert!(t.once);
+    }
+
+    #[test]
+    fn reject_bad_triggers() {
+        assert!(Trigger::parse("damage 3").is_err());
+        assert!(Trigger::parse("on enter:").is_err());
+        assert!(Trigger::parse("on enter: damage lots").is_err());
+        assert!(Trigger::parse("on enter: if owns key, gold 5").is_err());
+        assert!(Trigger::parse("on enter: dance").is_err());
+        assert!(Trigger::parse("on enter: say unquoted").is_err());
+    }
+}
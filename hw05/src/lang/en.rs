@@ -0,0 +1,135 @@
+pub static MESSAGES: &[(&str, &str)] = &[
+    ("event.chest", "You open the chest and gain {gold} gold."),
+    ("event.spike_trap", "You take {damage} damage from the spikes."),
+    ("event.food", "You shove a wall chicken into your gob and heal {hp} HP."),
+    ("event.iron_maiden", "Dude I love Iron Maiden! This one's pointy, though.\n\
+                           You cut yourself on the spikes inside for {damage} damage.\n\
+                           You open the iron maiden and..."),
+    ("event.fallen_adventurer", "You pilfer the corpse and..."),
+    ("event.potion", "You quaff a bubbling potion and your maximum HP rises by {hp}."),
+    ("event.key", "You pick up a rusty key."),
+    ("event.locked_chest", "You unlock the chest with a key and gain {gold} gold."),
+    ("event.chest_locked", "You find a locked chest, but you have no key."),
+    ("event.teleported", "A shimmering portal swallows you and spits you out in {room}!"),
+    ("event.quiver", "You find a quiver holding {arrows} arrows."),
+    ("event.damage", "You take {damage} damage."),
+    ("event.healed", "You recover {hp} HP."),
+    ("event.gold", "You find {gold} gold."),
+    ("event.item_gained", "You receive a {item}."),
+    ("event.item_lost", "You give up a {item}."),
+    ("event.hall_opened", "A hidden hall to {room} grinds open."),
+    ("event.monster_found", "A {name} bars your way!"),
+    ("event.monster_arrived", "A {name} prowls into the room!"),
+    ("event.monster_attacked", "The {name} strikes you for {damage} damage."),
+    ("event.monster_hit", "You strike the {name} for {damage} damage."),
+    ("event.monster_killed", "The {name} falls, dropping what it carried."),
+    ("event.fled", "You flee to {room}!"),
+    ("event.walked", "You walk to {room}."),
+    ("event.travel_stopped", "You stop short of {room} to take stock."),
+    ("event.eaten_by_wumpus", "You stumble into the wumpus's lair and it gobbles you up!"),
+    ("event.fell_in_pit", "The floor gives way and you tumble into a bottomless pit!"),
+    ("event.carried_by_bats", "A swarm of super bats snatches you up and drops you in {room}!"),
+    ("event.wumpus_killed", "Your arrow finds its mark and the wumpus lets out a final howl."),
+    ("event.arrow_missed", "Your arrow clatters against the stones. Somewhere, something stirs."),
+    ("event.wumpus_attacked", "The wumpus bursts into the room and devours you!"),
+    ("event.quiver_empty", "Your quiver is empty."),
+    ("event.time_passes", "Time passes."),
+
+    ("sense.wumpus", "You smell a wumpus."),
+    ("sense.draft", "You feel a draft."),
+    ("sense.flapping", "You hear flapping."),
+
+    ("player.no_exit", "There is no exit number {number}."),
+    ("player.no_way_back", "There is no way back from here."),
+    ("player.no_hall", "There is no hall to {room}."),
+    ("player.no_hall_suggest", "There is no hall to {room}. Did you mean {rooms}?"),
+    ("player.or", " or "),
+    ("player.unknown_room", "You have not been anywhere called {room}."),
+    ("player.already_there", "You are already in {room}."),
+    ("player.no_known_way", "You know of no way to {room} from here."),
+    ("player.monster_blocks", "The {name} blocks your way! Fight or flee."),
+    ("player.nothing_to_fight", "There is nothing here to fight."),
+    ("player.nothing_to_flee", "There is nothing here to flee from."),
+    ("player.status", "You have {hp} HP, {gold} gold and {arrows} arrows."),
+    ("player.carrying", "You carry: {items}."),
+
+    ("map.unexplored", "(unexplored)"),
+
+    ("ui.prompt", "What wouldst thou deau?"),
+    ("ui.parse_error", "I do not know how to parse {input}! Type help for a list of commands."),
+    ("ui.bye", "Bye forever :("),
+    ("ui.help", "\
+Commands:
+  go <room>, g <room>     walk through a hall; <room> may be a name, a prefix
+                          of a name, \"a quoted name\", an exit number or back
+  <number>, back, b       the same as go
+  north, east, south, west, or n, e, s, w
+                          go through exit 1, 2, 3 or 4
+  shoot <room>, fire <room>
+                          loose an arrow into a neighboring room
+  travel <room>, t <room> walk the shortest known way to a room you have
+                          been to, stopping if anything seems amiss
+  fight, f                attack a monster in the room
+  flee, r                 run from a monster back the way you came
+  wait, z                 let a turn pass
+  look, l                 describe the room again
+  inventory, inv, i       show your HP, gold and arrows
+  map, m                  show a map of the rooms you have visited
+  save <file>             save the game
+  load <file>             load a saved game
+  help, h, ?              show this message
+  quit, q                 give up"),
+    ("ui.monster_here", "A {name} with {hp} HP is here!"),
+    ("ui.location", "You find yourself in {room}."),
+    ("ui.exits", "Exits are: {exits}."),
+    ("ui.saved", "Game saved to {file}."),
+    ("ui.save_failed", "Unable to save to {file}: {error}"),
+    ("ui.loaded", "Game loaded from {file}."),
+    ("ui.load_failed", "Unable to load {file}: {error}"),
+    ("ui.not_recorded", "A loaded game cannot be replayed, so this one is no longer recorded."),
+    ("ui.replay_saved", "Replay saved to {file}."),
+    ("ui.replay_failed", "Unable to save the replay: {error}"),
+    ("ui.scores_read_failed", "Unable to read high scores: {error}"),
+    ("ui.scores_save_failed", "Unable to save high scores: {error}"),
+    ("ui.high_score", "A new high score, number {rank} on this level!"),
+    ("ui.high_scores", "High scores:"),
+    ("ui.turns", "{turns} turns"),
+    ("ui.won", "(won)"),
+    ("ui.summary", "\
+Rooms visited: {visited} of {rooms}
+Turns taken:   {turns}
+Damage taken:  {damage}
+Food eaten:    {food} ({healed} HP healed)
+Wumpus kills:  {kills}
+Monster kills: {monsters}
+Gold:          {gold}
+Score:         {score}
+"),
+    ("ui.win", "The wumpus is dead! The castle's treasures are yours.\nYou Win!"),
+    ("ui.lose_hp", "You try in vain to shovel more wall chicken into your mouth, \
+                    but you've been impaled by too many spikes or Wumpi :(\nYou Lose!"),
+    ("ui.lose_arrows", "Your quiver is empty and the wumpus still lurks in the dark.\nYou Lose!"),
+
+    ("tui.help", "1-9 go through an exit  b back  s 1-9 shoot  f fight  r flee  w wait  q quit"),
+    ("tui.shoot", "Shoot into which exit? 1-9, or Esc to cancel"),
+    ("tui.exits", "Exits"),
+    ("tui.status", "Status"),
+    ("tui.map", "Map"),
+    ("tui.log", "Log"),
+    ("tui.hp", "HP"),
+    ("tui.gold", "Gold"),
+    ("tui.arrows", "Arrows"),
+    ("tui.not_terminal", "Not a terminal; using the line interface."),
+
+    ("server.player", "Player {number}"),
+    ("server.welcome", "Welcome, {name}!"),
+    ("server.arrives", "{name} arrives."),
+    ("server.leaves", "{name} leaves."),
+    ("server.slain", "{name} has slain the wumpus!"),
+    ("server.hunt_over", "The hunt is over, and the castle falls quiet."),
+    ("server.no_saving", "Saving and loading are not available in a shared game."),
+
+    ("campaign.level", "{campaign}, level {level} of {levels}"),
+    ("campaign.ends", "Your campaign ends here."),
+    ("campaign.conquered", "You have conquered {campaign}!"),
+];
@@ -0,0 +1,134 @@
+pub static MESSAGES: &[(&str, &str)] = &[
+    ("event.chest", "宝箱を開けて{gold}ゴールドを手に入れた。"),
+    ("event.spike_trap", "棘の罠で{damage}のダメージを受けた。"),
+    ("event.food", "壁肉を口に押し込み、HPが{hp}回復した。"),
+    ("event.iron_maiden", "アイアン・メイデン最高！でもこいつは尖ってるな。\n\
+                           中の棘で{damage}のダメージを受けた。\n\
+                           アイアン・メイデンを開けると……"),
+    ("event.fallen_adventurer", "亡骸を漁ると……"),
+    ("event.potion", "泡立つ薬を飲み干すと、最大HPが{hp}上がった。"),
+    ("event.key", "錆びた鍵を拾った。"),
+    ("event.locked_chest", "鍵で宝箱を開け、{gold}ゴールドを手に入れた。"),
+    ("event.chest_locked", "鍵のかかった宝箱を見つけたが、鍵を持っていない。"),
+    ("event.teleported", "揺らめく門に飲み込まれ、{room}に吐き出された！"),
+    ("event.quiver", "矢が{arrows}本入った矢筒を見つけた。"),
+    ("event.damage", "{damage}のダメージを受けた。"),
+    ("event.healed", "HPが{hp}回復した。"),
+    ("event.gold", "{gold}ゴールドを見つけた。"),
+    ("event.item_gained", "{item}を手に入れた。"),
+    ("event.item_lost", "{item}を手放した。"),
+    ("event.hall_opened", "{room}への隠し通路が軋みながら開いた。"),
+    ("event.monster_found", "{name}が行く手を阻む！"),
+    ("event.monster_arrived", "{name}が部屋にうろつき込んできた！"),
+    ("event.monster_attacked", "{name}の攻撃で{damage}のダメージを受けた。"),
+    ("event.monster_hit", "{name}に{damage}のダメージを与えた。"),
+    ("event.monster_killed", "{name}は倒れ、持っていた物を落とした。"),
+    ("event.fled", "{room}へ逃げ出した！"),
+    ("event.walked", "{room}へ歩いた。"),
+    ("event.travel_stopped", "{room}に着く前に立ち止まり、様子をうかがう。"),
+    ("event.eaten_by_wumpus", "ワンパスの巣に迷い込み、丸呑みにされた！"),
+    ("event.fell_in_pit", "床が抜け、底なしの穴へ落ちていった！"),
+    ("event.carried_by_bats", "巨大コウモリの群れにさらわれ、{room}に落とされた！"),
+    ("event.wumpus_killed", "矢は見事に命中し、ワンパスは最期の咆哮を上げた。"),
+    ("event.arrow_missed", "矢は石壁に当たって乾いた音を立てた。どこかで何かが目を覚ます。"),
+    ("event.wumpus_attacked", "ワンパスが部屋に飛び込んできて、あなたを貪り食った！"),
+    ("event.quiver_empty", "矢筒は空だ。"),
+    ("event.time_passes", "時が過ぎる。"),
+
+    ("sense.wumpus", "ワンパスの臭いがする。"),
+    ("sense.draft", "隙間風を感じる。"),
+    ("sense.flapping", "羽ばたく音が聞こえる。"),
+
+    ("player.no_exit", "{number}番の出口はない。"),
+    ("player.no_way_back", "ここから戻る道はない。"),
+    ("player.no_hall", "{room}への通路はない。"),
+    ("player.no_hall_suggest", "{room}への通路はない。もしかして{rooms}？"),
+    ("player.or", "か"),
+    ("player.unknown_room", "{room}という場所には行ったことがない。"),
+    ("player.already_there", "すでに{room}にいる。"),
+    ("player.no_known_way", "ここから{room}への道を知らない。"),
+    ("player.monster_blocks", "{name}が行く手を阻んでいる！戦うか逃げるかだ。"),
+    ("player.nothing_to_fight", "ここには戦う相手がいない。"),
+    ("player.nothing_to_flee", "ここには逃げる相手がいない。"),
+    ("player.status", "HPは{hp}、ゴールドは{gold}、矢は{arrows}本。"),
+    ("player.carrying", "持ち物: {items}。"),
+
+    ("map.unexplored", "(未探索)"),
+
+    ("ui.prompt", "汝、何をせんとす？"),
+    ("ui.parse_error", "{input}の意味がわからない！helpでコマンドの一覧を表示する。"),
+    ("ui.bye", "永遠にさらば :("),
+    ("ui.help", "\
+コマンド:
+  go <部屋>, g <部屋>     通路を進む。<部屋>には名前、名前の先頭、
+                          \"引用符で囲んだ名前\"、出口の番号、backが使える
+  <番号>, back, b         goと同じ
+  north, east, south, west, または n, e, s, w
+                          出口1、2、3、4を進む
+  shoot <部屋>, fire <部屋>
+                          隣の部屋に矢を放つ
+  travel <部屋>, t <部屋> 訪れたことのある部屋へ知っている最短の道で向かう。
+                          異変があればそこで立ち止まる
+  fight, f                部屋の魔物を攻撃する
+  flee, r                 来た道を通って魔物から逃げる
+  wait, z                 一ターン待つ
+  look, l                 部屋の様子をもう一度見る
+  inventory, inv, i       HP、ゴールド、矢を表示する
+  map, m                  訪れた部屋の地図を表示する
+  save <ファイル>         ゲームをセーブする
+  load <ファイル>         セーブしたゲームをロードする
+  help, h, ?              このメッセージを表示する
+  quit, q                 諦める"),
+    ("ui.monster_here", "HP{hp}の{name}がいる！"),
+    ("ui.location", "あなたは{room}にいる。"),
+    ("ui.exits", "出口: {exits}。"),
+    ("ui.saved", "ゲームを{file}にセーブした。"),
+    ("ui.save_failed", "{file}にセーブできない: {error}"),
+    ("ui.loaded", "ゲームを{file}からロードした。"),
+    ("ui.load_failed", "{file}をロードできない: {error}"),
+    ("ui.not_recorded", "ロードしたゲームはリプレイできないので、このゲームはもう記録しない。"),
+    ("ui.replay_saved", "リプレイを{file}に保存した。"),
+    ("ui.replay_failed", "リプレイを保存できない: {error}"),
+    ("ui.scores_read_failed", "ハイスコアを読み込めない: {error}"),
+    ("ui.scores_save_failed", "ハイスコアを保存できない: {error}"),
+    ("ui.high_score", "新記録！このレベルの{rank}位だ！"),
+    ("ui.high_scores", "ハイスコア:"),
+    ("ui.turns", "{turns}ターン"),
+    ("ui.won", "(勝利)"),
+    ("ui.summary", "\
+訪れた部屋:   {visited} / {rooms}
+経過ターン:   {turns}
+被ダメージ:   {damage}
+食べた壁肉:   {food} (HP {healed} 回復)
+ワンパス討伐: {kills}
+魔物討伐:     {monsters}
+ゴールド:     {gold}
+スコア:       {score}
+"),
+    ("ui.win", "ワンパスは死んだ！城の財宝はあなたのものだ。\nあなたの勝ち！"),
+    ("ui.lose_hp", "さらに壁肉を口に詰め込もうとしたが、棘かワンパスにやられすぎた :(\nあなたの負け！"),
+    ("ui.lose_arrows", "矢筒は空になり、ワンパスはまだ闇に潜んでいる。\nあなたの負け！"),
+
+    ("tui.help", "1-9 出口へ進む   b 戻る   s と 1-9 射る   f 戦う   r 逃げる   w 待つ   q 終了"),
+    ("tui.shoot", "どの出口に射る？ 1-9、Escで取り消し"),
+    ("tui.exits", "出口"),
+    ("tui.status", "状態"),
+    ("tui.map", "地図"),
+    ("tui.log", "記録"),
+    ("tui.hp", "HP"),
+    ("tui.gold", "ゴールド"),
+    ("tui.arrows", "矢"),
+    ("tui.not_terminal", "端末ではないので、行単位のインターフェースを使う。"),
+
+    ("server.player", "プレイヤー{number}"),
+    ("server.welcome", "ようこそ、{name}！"),
+    ("server.arrives", "{name}がやって来た。"),
+    ("server.leaves", "{name}が去った。"),
+    ("server.slain", "{name}がワンパスを倒した！"),
+    ("server.hunt_over", "狩りは終わり、城に静けさが戻った。"),
+    ("server.no_saving", "共有ゲームではセーブとロードはできない。"),
+
+    ("campaign.level", "{campaign} レベル {level} / {levels}"),
+    ("campaign.ends", "キャンペーンはここで終わりだ。"),
+    ("campaign.conquered", "{campaign}を制覇した！"),
+];
@@ -0,0 +1,160 @@
+//! The text of the game in each language it speaks.
+//!
+//! Every message has an ID, like `event.chest`, and a template per language,
+//! like `You open the chest and gain {gold} gold.`, whose `{name}`
+//! placeholders are filled in when the message is shown. Messages missing
+//! from a catalog fall back to English. Level text such as room names and
+//! descriptions is shown as the level wrote it.
+
+use std::env;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod en;
+mod ja;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    English,
+    Japanese,
+}
+
+const LANGS: [Lang; 2] = [Lang::English, Lang::Japanese];
+
+/// The language messages are shown in, as an index into `LANGS`.
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+impl Lang {
+    /// The language's code, as given to `--lang`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::English => "en",
+            Lang::Japanese => "ja",
+        }
+    }
+
+    /// The language for a code like `ja`, or a locale like `ja_JP.UTF-8`.
+    pub fn from_code(code: &str) -> Option<Lang> {
+        let code = code.to_lowercase();
+        let code = code.split(['_', '-', '.']).next().unwrap_or("");
+        LANGS.iter().cloned().find(|l| l.code() == code)
+    }
+
+    /// The language of the environment's locale, or English if it names no
+    /// language the game speaks.
+    pub fn from_env() -> Lang {
+        ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|val| !val.is_empty())
+            .and_then(|val| Lang::from_code(&val))
+            .unwrap_or(Lang::English)
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::English => en::MESSAGES,
+            Lang::Japanese => ja::MESSAGES,
+        }
+    }
+}
+
+/// Show messages in `lang` from now on.
+pub fn set(lang: Lang) {
+    let index = LANGS.iter().position(|&l| l == lang).unwrap_or(0);
+    CURRENT.store(index, Ordering::Relaxed);
+}
+
+pub fn current() -> Lang {
+    LANGS[CURRENT.load(Ordering::Relaxed)]
+}
+
+/// The template for message `id` in `lang`.
+fn template(lang: Lang, id: &str) -> Option<&'static str> {
+    let find = |lang: Lang| lang.catalog().iter().find(|&&(key, _)| key == id).map(|&(_, text)| text);
+    find(lang).or_else(|| find(Lang::English))
+}
+
+/// Message `id` in the current language with its placeholders filled in
+/// from `args`. Unknown IDs come out as the ID itself, so they are easy to
+/// spot.
+pub fn format(id: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    format_in(current(), id, args)
+}
+
+pub fn format_in(lang: Lang, id: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut rest = match template(lang, id) {
+        Some(text) => text,
+        None => return id.to_string(),
+    };
+    let mut out = String::new();
+    while let Some(open) = rest.find('{') {
+        let close = match rest[open..].find('}') {
+            Some(close) => open + close,
+            None => break,
+        };
+        out.push_str(&rest[..open]);
+        let name = &rest[open + 1..close];
+        match args.iter().find(|&&(arg, _)| arg == name) {
+            Some(&(_, val)) => out.push_str(&val.to_string()),
+            None => out.push_str(&rest[open..=close]),
+        }
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Lets `t!` put arguments of different types in one list.
+pub fn arg<T: fmt::Display>(val: &T) -> &dyn fmt::Display {
+    val
+}
+
+/// Message `id` in the current language, e.g. `t!("event.chest", gold = 5)`.
+macro_rules! t {
+    ($id:expr) => {
+        $crate::lang::format($id, &[])
+    };
+    ($id:expr, $($name:ident = $val:expr),+) => {
+        $crate::lang::format($id, &[$((stringify!($name), $crate::lang::arg(&$val))),+])
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_in, Lang, LANGS};
+
+    /// The placeholders a template uses, in order.
+    fn placeholders(text: &str) -> Vec<&str> {
+        text.split('{').skip(1).filter_map(|s| s.split('}').next()).collect()
+    }
+
+    #[test]
+    fn catalogs_match() {
+        let english = Lang::English.catalog();
+        for lang in &LANGS {
+            let catalog = lang.catalog();
+            for (i, &(id, text)) in catalog.iter().enumerate() {
+                assert!(catalog[..i].iter().all(|&(other, _)| other != id), "{} is in {:?} twice", id, lang);
+                let en = english.iter().find(|&&(key, _)| key == id);
+                assert!(en.is_some(), "{} is in {:?} but not English", id, lang);
+                let mut theirs = placeholders(text);
+                let mut ours = placeholders(en.unwrap().1);
+                theirs.sort();
+                ours.sort();
+                assert_eq!(theirs, ours, "{} has different placeholders in {:?}", id, lang);
+            }
+            assert_eq!(catalog.len(), english.len(), "{:?} is missing messages", lang);
+        }
+    }
+
+    #[test]
+    fn fill_placeholders() {
+        assert_eq!(format_in(Lang::English, "event.chest", &[("gold", &12)]), "You open the chest and gain 12 gold.");
+        assert_eq!(format_in(Lang::Japanese, "event.chest", &[("gold", &12)]), "宝箱を開けて12ゴールドを手に入れた。");
+        assert_eq!(format_in(Lang::English, "event.chest", &[]), "You open the chest and gain {gold} gold.");
+        assert_eq!(format_in(Lang::Japanese, "no.such.message", &[]), "no.such.message");
+        assert_eq!(Lang::from_code("ja_JP.UTF-8"), Some(Lang::Japanese));
+        assert_eq!(Lang::from_code("EN"), Some(Lang::English));
+        assert_eq!(Lang::from_code("C"), None);
+    }
+}
@@ -1,23 +1,246 @@
+#[macro_use]
+mod lang;
 pub mod game;
+mod parser;
+mod server;
+mod tui;
 mod ui;
 
 extern crate rand;
 extern crate rustc_serialize;
 
 use std::env::args;
-use std::fs::File;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
 
+use rustc_serialize::json::Json;
+
+use game::agent::{self, GreedyExplorer, RandomWalker};
 use game::board::Board;
+use game::campaign::Campaign;
+use game::difficulty::Difficulty;
+use game::generate::Params;
 use game::player::Player;
+use game::room::RoomId;
+use game::replay::{self, Replay};
+use lang::Lang;
 
 fn main() {
-    let mut args = args();
-    if let Some(filename) = args.nth(1) {
-        let mut file_handle = File::open(&filename).expect(&format!("Unable to open file {}", filename));
+    let mut args: Vec<String> = args().collect();
+    lang::set(Lang::from_env());
+    if let Some(i) = args.iter().position(|a| a == "--lang") {
+        let code = args.get(i + 1).cloned().unwrap_or_else(|| usage());
+        lang::set(Lang::from_code(&code).unwrap_or_else(|| usage()));
+        args.drain(i..i + 2);
+    }
+    match args.get(1).map(|a| &a[..]) {
+        Some("generate") => generate(&args[2..]),
+        Some("simulate") => simulate(&args[2..]),
+        Some("replay") => play_replay(&args[2..]),
+        Some("dot") => {
+            let filename = args.get(2).unwrap_or_else(|| usage());
+            let mut file_handle = File::open(filename).unwrap_or_else(|_| panic!("Unable to open file {}", filename));
+            let board = Board::build_board(&mut file_handle).unwrap();
+            print!("{}", board.to_dot());
+        },
+        Some("campaign") => campaign(&args[2..]),
+        Some(filename) => play(filename, &args[2..]),
+        None => usage(),
+    }
+}
+
+fn usage() -> ! {
+    println!("Usage: ./main file_name.json | cargo run -- file_name.json");
+    println!("       ./main file_name.json [--tui] [--difficulty easy|normal|hard]");
+    println!("       ./main file_name.json --serve 127.0.0.1:PORT [--difficulty easy|normal|hard]");
+    println!("       ./main campaign [--tui] [--difficulty easy|normal|hard] campaign.json");
+    println!("       ./main generate [--seed N] [--rooms N] [--degree X] [--curios X] \
+              [--pits N] [--bats N] > level.json");
+    println!("       ./main dot file_name.json > castle.dot");
+    println!("       ./main simulate [--games N] [--seed N] [--agent greedy|random] file_name.json");
+    println!("       ./main replay [--check] file_name.replay.json");
+    println!("Any of these take --lang en|ja to choose the language of the game, which otherwise");
+    println!("follows the locale.");
+    process::exit(1);
+}
+
+/// Play a single level, or share it over TCP with `--serve`.
+fn play(filename: &str, opts: &[String]) {
+    let mut tui = false;
+    let mut serve = None;
+    let mut difficulty = Difficulty::default();
+    let mut opts = opts.iter();
+    while let Some(opt) = opts.next() {
+        match &opt[..] {
+            "--tui" => tui = true,
+            "--serve" => serve = Some(opts.next().unwrap_or_else(|| usage())),
+            "--difficulty" => difficulty = parse_difficulty(opts.next()),
+            _ => usage(),
+        }
+    }
+    if let Some(addr) = serve {
+        let mut file_handle = File::open(filename).unwrap_or_else(|_| panic!("Unable to open file {}", filename));
         let board = Board::build_board(&mut file_handle).unwrap();
-        let player = Player::new(board.spawn_location());
-        ui::game_loop(player);
+        server::serve(board, difficulty, addr).unwrap_or_else(|e| panic!("Unable to serve on {}: {}", addr, e));
+        return;
+    }
+    play_level(filename, &Player::with_difficulty(RoomId(0), difficulty), tui);
+}
+
+/// Play the levels of a campaign in order for as long as the player wins.
+fn campaign(opts: &[String]) {
+    let mut tui = false;
+    let mut difficulty = None;
+    let mut filename = None;
+    let mut opts = opts.iter();
+    while let Some(opt) = opts.next() {
+        match &opt[..] {
+            "--tui" => tui = true,
+            "--difficulty" => difficulty = Some(parse_difficulty(opts.next())),
+            _ => filename = Some(opt),
+        }
+    }
+    let filename = filename.unwrap_or_else(|| usage());
+    let campaign = Campaign::load(Path::new(filename)).unwrap_or_else(|e| panic!("Unable to load {}: {}", filename, e));
+    let difficulty = difficulty.or(campaign.difficulty).unwrap_or_default();
+
+    let mut player = Player::with_difficulty(RoomId(0), difficulty);
+    for (i, level) in campaign.levels.iter().enumerate() {
+        println!("\n{}", t!("campaign.level", campaign = campaign.name, level = i + 1, levels = campaign.levels.len()));
+        player = play_level(&level.to_string_lossy(), &player, tui);
+        if !player.won {
+            println!("\n{}", t!("campaign.ends"));
+            return;
+        }
+    }
+    println!("\n{}", t!("campaign.conquered", campaign = campaign.name));
+}
+
+/// Play the level in `filename`, with the player starting out as `before`,
+/// and return the player as they finished.
+fn play_level(filename: &str, before: &Player, tui: bool) -> Player {
+    let text = fs::read_to_string(filename).unwrap_or_else(|_| panic!("Unable to open file {}", filename));
+    let level = Json::from_str(&text).unwrap_or_else(|_| panic!("Unable to parse {}", filename));
+    let seed: u64 = rand::random();
+    let mut board = Board::from_json_seeded(&level, seed).unwrap();
+    let (player, events) = before.next_level(&mut board);
+    let replay = Replay::new(Path::new(filename), replay::level_hash(text.as_bytes()), seed, before, &events);
+    if tui {
+        tui::game_loop(board, player, events, Path::new(filename), replay)
+    } else {
+        for e in events {
+            println!("{}", e);
+        }
+        ui::game_loop(board, player, Path::new(filename), replay)
+    }
+}
+
+fn parse_difficulty(name: Option<&String>) -> Difficulty {
+    name.and_then(|n| Difficulty::from_name(n)).unwrap_or_else(|| usage())
+}
+
+/// Print a randomly generated level to stdout.
+fn generate(opts: &[String]) {
+    let mut params = Params::default();
+    let mut seed: u64 = rand::random();
+    let mut opts = opts.iter();
+    while let Some(opt) = opts.next() {
+        let val = opts.next().unwrap_or_else(|| usage());
+        match &opt[..] {
+            "--seed" => seed = parse_opt(val),
+            "--rooms" => params.rooms = parse_opt(val),
+            "--degree" => params.degree = parse_opt(val),
+            "--curios" => params.curio_density = parse_opt(val),
+            "--pits" => params.pits = parse_opt(val),
+            "--bats" => params.bats = parse_opt(val),
+            _ => usage(),
+        }
+    }
+    println!("{}", Board::generate(&params, seed).to_json().pretty());
+}
+
+/// Play many games of a level with an automated player and report how it went.
+fn simulate(opts: &[String]) {
+    let mut games: usize = 1000;
+    let mut greedy = true;
+    let mut seed: u64 = rand::random();
+    let mut filename = None;
+    let mut opts = opts.iter();
+    while let Some(opt) = opts.next() {
+        match &opt[..] {
+            "--games" => games = parse_opt(opts.next().unwrap_or_else(|| usage())),
+            "--seed" => seed = parse_opt(opts.next().unwrap_or_else(|| usage())),
+            "--agent" => match opts.next().map(|a| &a[..]) {
+                Some("greedy") => greedy = true,
+                Some("random") => greedy = false,
+                _ => usage(),
+            },
+            _ => filename = Some(opt),
+        }
+    }
+    let filename = filename.unwrap_or_else(|| usage());
+    if games == 0 {
+        usage();
+    }
+    let mut file_handle = File::open(filename).unwrap_or_else(|_| panic!("Unable to open file {}", filename));
+    let level = Json::from_reader(&mut file_handle).unwrap_or_else(|_| panic!("Unable to parse {}", filename));
+
+    let stats = if greedy {
+        agent::simulate(&level, games, seed, GreedyExplorer::new)
     } else {
-        println!("Usage: ./main file_name.json | cargo run -- file_name.json");
+        agent::simulate(&level, games, seed, RandomWalker::new)
+    }.unwrap();
+    println!("Seed:      {}", seed);
+    println!("Games:     {}", stats.games);
+    println!("Win rate:  {:.1}%", stats.win_rate() * 100.0);
+    println!("Avg gold:  {:.1}", stats.avg_gold());
+    println!("Avg HP:    {:.1}", stats.avg_hp());
+}
+
+/// Play back a recorded game. With `--check`, exit with an error if it does
+/// not go exactly as it did when it was recorded.
+fn play_replay(opts: &[String]) {
+    let (check, filename) = match opts {
+        [flag, filename] if flag == "--check" => (true, filename),
+        [filename] => (false, filename),
+        _ => usage(),
+    };
+    let fail = |msg: String| -> ! {
+        println!("{}", msg);
+        process::exit(1);
+    };
+    let recorded = Replay::load(Path::new(filename)).unwrap_or_else(|e| fail(e));
+    // The recording holds what the game printed, so play it back in the
+    // language it was recorded in.
+    lang::set(recorded.lang);
+    let level_path = recorded.level_path(Path::new(filename));
+    let text = fs::read_to_string(&level_path)
+        .unwrap_or_else(|e| fail(format!("Unable to open level {}: {}", level_path.display(), e)));
+    if replay::level_hash(text.as_bytes()) != recorded.level_hash {
+        fail(format!("{} has changed since the replay was recorded.", level_path.display()));
+    }
+    let level = Json::from_str(&text).unwrap_or_else(|_| fail(format!("Unable to parse {}", level_path.display())));
+    let played = recorded.play(&level).unwrap_or_else(|e| fail(e));
+
+    for line in &played.start {
+        println!("{}", line);
     }
+    for (cmd, output) in played.commands.iter().zip(&played.output) {
+        println!("> {}", cmd);
+        for line in output {
+            println!("{}", line);
+        }
+    }
+    if check {
+        match recorded.diff(&played) {
+            Some(diff) => fail(format!("Replay differs: {}", diff)),
+            None => println!("Replay matches the recording."),
+        }
+    }
+}
+
+fn parse_opt<T: FromStr>(val: &str) -> T {
+    val.parse().unwrap_or_else(|_| usage())
 }
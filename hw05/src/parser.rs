@@ -0,0 +1,136 @@
+use game::player::Command;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    Parse,
+    Quit,
+}
+
+/// A line of input: either a command for the player or a request for the
+/// interface itself.
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    Cmd(Command),
+    Look,
+    Inventory,
+    Map,
+    Help,
+    Save(String),
+    Load(String),
+}
+
+/// Split a line into words. Double quotes group several words into one.
+fn tokenize(buf: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut cur = String::new();
+    let mut quoted = false;
+    let mut in_token = false;
+    for c in buf.chars() {
+        if c == '"' {
+            quoted = !quoted;
+            in_token = true;
+        } else if c.is_whitespace() && !quoted {
+            if in_token {
+                tokens.push(cur.clone());
+                cur.clear();
+                in_token = false;
+            }
+        } else {
+            cur.push(c);
+            in_token = true;
+        }
+    }
+    if in_token {
+        tokens.push(cur);
+    }
+    tokens
+}
+
+pub fn parse_line(buf: &str) -> Result<Input, Error> {
+    use game::player::Command::*;
+
+    let tokens = tokenize(buf);
+    let mut tokens = tokens.into_iter();
+
+    let cmd = tokens.next().ok_or(Error::Parse)?.to_lowercase();
+    // Everything after the verb names its target, so unquoted names with
+    // spaces work too.
+    let rest: Vec<String> = tokens.collect();
+    let target = || -> Result<String, Error> {
+        if rest.is_empty() {
+            Err(Error::Parse)
+        } else {
+            Ok(rest.join(" ").to_lowercase())
+        }
+    };
+    let filename = || -> Result<String, Error> {
+        if rest.len() == 1 { Ok(rest[0].clone()) } else { Err(Error::Parse) }
+    };
+
+    match &cmd[..] {
+        "go" | "g" | "walk" | "move" => Ok(Input::Cmd(Go(target()?))),
+        "back" | "b" => Ok(Input::Cmd(Go("back".to_string()))),
+        "north" | "n" if rest.is_empty() => Ok(Input::Cmd(Go("north".to_string()))),
+        "east" | "e" if rest.is_empty() => Ok(Input::Cmd(Go("east".to_string()))),
+        "south" | "s" if rest.is_empty() => Ok(Input::Cmd(Go("south".to_string()))),
+        "west" | "w" if rest.is_empty() => Ok(Input::Cmd(Go("west".to_string()))),
+        "shoot" | "fire" => Ok(Input::Cmd(Shoot(target()?))),
+        "travel" | "t" => Ok(Input::Cmd(Travel(target()?))),
+        "fight" | "f" | "attack" => Ok(Input::Cmd(Fight)),
+        "flee" | "r" | "run" => Ok(Input::Cmd(Flee)),
+        "wait" | "z" => Ok(Input::Cmd(Wait)),
+        "look" | "l" => Ok(Input::Look),
+        "inventory" | "inv" | "i" => Ok(Input::Inventory),
+        "map" | "m" => Ok(Input::Map),
+        "help" | "h" | "?" => Ok(Input::Help),
+        "save" => Ok(Input::Save(filename()?)),
+        "load" => Ok(Input::Load(filename()?)),
+        "quit" | "q" | "exit" => Err(Error::Quit),
+        _ if cmd.parse::<usize>().is_ok() && rest.is_empty() => Ok(Input::Cmd(Go(cmd))),
+        _ => Err(Error::Parse),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use game::player::Command::*;
+    use parser::{parse_line, Error, Input};
+
+    #[test]
+    fn parse_aliases() {
+        assert_eq!(parse_line("go Foyer"), Ok(Input::Cmd(Go("foyer".to_string()))));
+        assert_eq!(parse_line("g foyer\n"), Ok(Input::Cmd(Go("foyer".to_string()))));
+        assert_eq!(parse_line("2"), Ok(Input::Cmd(Go("2".to_string()))));
+        assert_eq!(parse_line("b"), Ok(Input::Cmd(Go("back".to_string()))));
+        assert_eq!(parse_line("fire lair"), Ok(Input::Cmd(Shoot("lair".to_string()))));
+        assert_eq!(parse_line("n"), Ok(Input::Cmd(Go("north".to_string()))));
+        assert_eq!(parse_line("S"), Ok(Input::Cmd(Go("south".to_string()))));
+        assert_eq!(parse_line("west"), Ok(Input::Cmd(Go("west".to_string()))));
+        assert_eq!(parse_line("t Great Hall"), Ok(Input::Cmd(Travel("great hall".to_string()))));
+        assert_eq!(parse_line("f"), Ok(Input::Cmd(Fight)));
+        assert_eq!(parse_line("run"), Ok(Input::Cmd(Flee)));
+        assert_eq!(parse_line("z"), Ok(Input::Cmd(Wait)));
+        assert_eq!(parse_line("i"), Ok(Input::Inventory));
+        assert_eq!(parse_line("l"), Ok(Input::Look));
+        assert_eq!(parse_line("m"), Ok(Input::Map));
+        assert_eq!(parse_line("?"), Ok(Input::Help));
+        assert_eq!(parse_line("q"), Err(Error::Quit));
+    }
+
+    #[test]
+    fn parse_names_with_spaces() {
+        assert_eq!(parse_line("go \"Great Hall\""), Ok(Input::Cmd(Go("great hall".to_string()))));
+        assert_eq!(parse_line("go great   hall"), Ok(Input::Cmd(Go("great hall".to_string()))));
+        assert_eq!(parse_line("save My.json"), Ok(Input::Save("My.json".to_string())));
+        assert_eq!(parse_line("save \"my game.json\""), Ok(Input::Save("my game.json".to_string())));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse_line(""), Err(Error::Parse));
+        assert_eq!(parse_line("go"), Err(Error::Parse));
+        assert_eq!(parse_line("dance"), Err(Error::Parse));
+        assert_eq!(parse_line("save a b"), Err(Error::Parse));
+        assert_eq!(parse_line("s lair"), Err(Error::Parse));
+    }
+}
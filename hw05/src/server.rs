@@ -0,0 +1,270 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use game::board::Board;
+use game::difficulty::Difficulty;
+use game::event::Event;
+use game::player::Player;
+use game::room::RoomId;
+use parser::{self, Error, Input};
+use ui;
+
+/// How long a write to a client may block before they are dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A connected player.
+struct Client {
+    name: String,
+    player: Player,
+    /// Output waiting for the client's writer thread, or `None` once they
+    /// have left.
+    outbox: Option<Sender<String>>,
+}
+
+impl Client {
+    fn online(&self) -> bool {
+        self.outbox.is_some()
+    }
+}
+
+/// Start a thread that writes everything sent to the returned queue to
+/// `stream`, so a client who stops reading only holds up their own output.
+/// Once the queue is dropped and drained, the connection is shut down.
+fn spawn_writer(mut stream: TcpStream) -> Sender<String> {
+    let (tx, rx) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+        for msg in rx {
+            if stream.write_all(msg.as_bytes()).is_err() {
+                break;
+            }
+        }
+        let _ = stream.shutdown(Shutdown::Both);
+    });
+    tx
+}
+
+/// The shared game: one board and everyone playing on it.
+struct World {
+    board: Board,
+    difficulty: Difficulty,
+    clients: Vec<Client>,
+    /// Set once someone has slain the wumpus, ending the game for everyone.
+    over: bool,
+}
+
+impl World {
+    /// Queue `msg` for one client. Errors are ignored; the client's own
+    /// thread notices the broken connection.
+    fn send(&mut self, id: usize, msg: &str) {
+        if let Some(ref outbox) = self.clients[id].outbox {
+            let _ = outbox.send(msg.to_string());
+        }
+    }
+
+    /// Send `msg` to every other online client standing in `room`.
+    fn broadcast_room(&mut self, from: usize, room: RoomId, msg: &str) {
+        for id in 0..self.clients.len() {
+            if id != from && self.clients[id].player.location == room && self.clients[id].player.hp > 0 {
+                self.send(id, msg);
+            }
+        }
+    }
+
+    /// Send `msg` to every other online client.
+    fn broadcast_all(&mut self, from: usize, msg: &str) {
+        for id in 0..self.clients.len() {
+            if id != from {
+                self.send(id, msg);
+            }
+        }
+    }
+
+    /// Put a new player in the spawn room and greet them. Once the game is
+    /// over, they are told so and let go.
+    fn join(&mut self, stream: TcpStream) -> usize {
+        let id = self.clients.len();
+        if self.over {
+            let name = t!("server.player", number = id + 1);
+            let player = Player::new(self.board.spawn_location());
+            self.clients.push(Client { name, player, outbox: Some(spawn_writer(stream)) });
+            self.send(id, &format!("{}\n", t!("server.hunt_over")));
+            self.leave(id);
+            return id;
+        }
+        let (player, events) = Player::spawn(&mut self.board, self.difficulty);
+        let location = player.location;
+        let outbox = Some(spawn_writer(stream));
+        self.clients.push(Client { name: t!("server.player", number = id + 1), player, outbox });
+
+        let mut out = format!("{}\n", t!("server.welcome", name = self.clients[id].name));
+        for e in events {
+            out.push_str(&format!("{}\n", e));
+        }
+        out.push_str(&ui::describe(&self.board, &self.clients[id].player));
+        self.send(id, &out);
+        let msg = format!("{}\n", t!("server.arrives", name = self.clients[id].name));
+        self.broadcast_room(id, location, &msg);
+        id
+    }
+
+    /// Carry out one line of input from client `id`. Returns false once the
+    /// client is done playing.
+    fn step(&mut self, id: usize, line: &str) -> bool {
+        let name = self.clients[id].name.clone();
+        let before = self.clients[id].player.location;
+        let mut out = String::new();
+
+        match parser::parse_line(line) {
+            Err(Error::Parse) => {
+                out.push_str(&format!("{}\n", t!("ui.parse_error", input = line.trim())));
+            },
+            Err(Error::Quit) => {
+                self.send(id, &format!("{}\n", t!("ui.bye")));
+                return false;
+            },
+            Ok(Input::Cmd(cmd)) => {
+                let others: Vec<RoomId> = self.clients.iter().enumerate()
+                    .filter(|&(other, c)| other != id && c.online() && c.player.hp > 0)
+                    .map(|(_, c)| c.player.location)
+                    .collect();
+                let result = self.clients[id].player.act_among(&mut self.board, cmd, &others);
+                match result {
+                    Ok(events) => {
+                        for e in events {
+                            out.push_str(&format!("{}\n", e));
+                        }
+                    },
+                    Err(e) => out.push_str(&format!("{}\n", e)),
+                }
+            },
+            Ok(Input::Look) => out.push_str(&ui::describe(&self.board, &self.clients[id].player)),
+            Ok(Input::Inventory) => out.push_str(&format!("{}\n", self.clients[id].player.status())),
+            Ok(Input::Map) => {
+                let player = &self.clients[id].player;
+                out.push_str(&self.board.to_ascii(player.location, &player.stats.rooms_visited));
+            },
+            Ok(Input::Help) => out.push_str(&format!("{}\n", t!("ui.help"))),
+            Ok(Input::Save(_)) | Ok(Input::Load(_)) => {
+                out.push_str(&format!("{}\n", t!("server.no_saving")));
+            },
+        }
+
+        let after = self.clients[id].player.location;
+        if after != before {
+            self.broadcast_room(id, before, &format!("{}\n", t!("server.leaves", name = name)));
+            self.broadcast_room(id, after, &format!("{}\n", t!("server.arrives", name = name)));
+            if self.clients[id].player.hp > 0 {
+                out.push_str(&ui::describe(&self.board, &self.clients[id].player));
+            }
+        }
+        if self.clients[id].player.won {
+            self.broadcast_all(id, &format!("{}\n", t!("server.slain", name = name)));
+            self.end_game(id);
+        }
+        self.wumpus_attacks(id);
+
+        if let Some(msg) = ui::game_over(&self.clients[id].player) {
+            out.push_str(&format!("{}\n", msg));
+            out.push_str(&ui::summary(&self.board, &self.clients[id].player));
+            self.send(id, &out);
+            return false;
+        }
+        self.send(id, &out);
+        true
+    }
+
+    /// A startled wumpus may have wandered in on other players.
+    fn wumpus_attacks(&mut self, from: usize) {
+        let lair = match self.board.wumpus_location() {
+            Some(lair) => lair,
+            None => return,
+        };
+        for id in 0..self.clients.len() {
+            let client = &self.clients[id];
+            if id != from && client.online() && client.player.location == lair && client.player.hp > 0 {
+                self.clients[id].player.kill();
+                let msg = format!("{}\n{}\n{}", Event::WumpusAttacked,
+                                  ui::game_over(&self.clients[id].player).unwrap(),
+                                  ui::summary(&self.board, &self.clients[id].player));
+                self.send(id, &msg);
+                self.leave(id);
+            }
+        }
+    }
+
+    /// The wumpus is dead, so send everyone but the winner `from` home with
+    /// their summary.
+    fn end_game(&mut self, from: usize) {
+        self.over = true;
+        for id in 0..self.clients.len() {
+            if id != from && self.clients[id].online() && self.clients[id].player.hp > 0 {
+                let msg = format!("{}\n{}", t!("server.hunt_over"), ui::summary(&self.board, &self.clients[id].player));
+                self.send(id, &msg);
+                self.leave(id);
+            }
+        }
+    }
+
+    /// Disconnect client `id` once their queued output has been written.
+    fn leave(&mut self, id: usize) {
+        if self.clients[id].outbox.take().is_none() {
+            return;
+        }
+        if self.clients[id].player.hp > 0 {
+            let msg = format!("{}\n", t!("server.leaves", name = self.clients[id].name));
+            let location = self.clients[id].player.location;
+            self.broadcast_room(id, location, &msg);
+        }
+    }
+}
+
+/// Run a shared game on `board`, accepting line-based commands from any
+/// number of clients connecting to `addr`.
+pub fn serve(board: Board, difficulty: Difficulty, addr: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!("Listening on {}", listener.local_addr()?);
+    io::stdout().flush()?;
+
+    let world = Arc::new(Mutex::new(World { board, difficulty, clients: Vec::new(), over: false }));
+    for stream in listener.incoming() {
+        // A connection that fails before it is accepted only costs that
+        // player, not everyone already in the game.
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("Accept error: {}", e);
+                continue;
+            },
+        };
+        let world = world.clone();
+        thread::spawn(move || {
+            if let Err(e) = handle_client(&world, stream) {
+                println!("Client error: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_client(world: &Mutex<World>, stream: TcpStream) -> io::Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let id = world.lock().unwrap().join(stream);
+
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let mut world = world.lock().unwrap();
+        if !world.clients[id].online() || !world.step(id, &line) {
+            break;
+        }
+    }
+    world.lock().unwrap().leave(id);
+    Ok(())
+}
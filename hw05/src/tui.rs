@@ -0,0 +1,297 @@
+use std::cmp;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process::{Command as Shell, Stdio};
+
+use game::board::Board;
+use game::event::Event;
+use game::player::{Command, Player};
+use game::replay::Replay;
+use ui;
+
+const DEFAULT_SIZE: (usize, usize) = (24, 80);
+const MIN_SIZE: (usize, usize) = (16, 50);
+
+/// Puts the terminal in a mode where keys arrive as soon as they are pressed,
+/// on an alternate screen, and puts it back when dropped.
+struct Screen {
+    saved: String,
+}
+
+impl Screen {
+    fn open() -> Option<Screen> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "1"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        Some(Screen { saved: saved.trim().to_string() })
+    }
+
+    /// The terminal's rows and columns.
+    fn size(&self) -> (usize, usize) {
+        let size = stty(&["size"]).and_then(|s| {
+            let mut dims = s.split_whitespace().map(|d| d.parse::<usize>().ok());
+            Some((dims.next()??, dims.next()??))
+        });
+        let (rows, cols) = size.unwrap_or(DEFAULT_SIZE);
+        (cmp::max(rows, MIN_SIZE.0), cmp::max(cols, MIN_SIZE.1))
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        stty(&[&self.saved]);
+    }
+}
+
+/// Run `stty` on the terminal, returning its output if it succeeded.
+fn stty(args: &[&str]) -> Option<String> {
+    let out = Shell::new("stty").args(args).stdin(Stdio::inherit()).output().ok()?;
+    if out.status.success() { String::from_utf8(out.stdout).ok() } else { None }
+}
+
+/// Play a game in a full-screen interface, falling back to the line-based
+/// one if stdin and stdout are not both terminals. `events` are what happened
+/// as the player arrived; the game is recorded in `replay`. Returns the
+/// player as they finished.
+pub fn game_loop(board: Board, player: Player, events: Vec<Event>, level: &Path, mut replay: Replay) -> Player {
+    let screen = if io::stdin().is_terminal() && io::stdout().is_terminal() { Screen::open() } else { None };
+    let screen = match screen {
+        Some(screen) => screen,
+        None => {
+            eprintln!("{}", t!("tui.not_terminal"));
+            for e in events {
+                println!("{}", e);
+            }
+            return ui::game_loop(board, player, level, replay);
+        },
+    };
+    let (board, player, ending) = run(&screen, board, player, events, &mut replay);
+    drop(screen);
+
+    if let Some(msg) = ending {
+        println!("{}", msg);
+    }
+    print!("\n{}", ui::summary(&board, &player));
+    ui::record_score(&player, level);
+    ui::save_replay(&replay, level);
+    player
+}
+
+/// The turn loop. Returns the final state and the closing message, if the
+/// game ended rather than being quit.
+fn run(screen: &Screen, mut board: Board, mut player: Player, events: Vec<Event>, replay: &mut Replay)
+       -> (Board, Player, Option<String>) {
+    let mut log = Vec::new();
+    push_events(&mut log, events);
+    let mut shooting = false;
+    let stdin = io::stdin();
+    let mut keys = stdin.lock().bytes();
+
+    loop {
+        if let Some(msg) = ui::game_over(&player) {
+            return (board, player, Some(msg));
+        }
+        let (rows, cols) = screen.size();
+        print!("\x1b[H\x1b[2J{}", render(&board, &player, &log, shooting, rows, cols));
+        let _ = io::stdout().flush();
+
+        let key = match keys.next() {
+            Some(Ok(key)) => key as char,
+            _ => return (board, player, None),
+        };
+        let cmd = match (shooting, key) {
+            (_, '\x1b') => {
+                shooting = false;
+                continue;
+            },
+            (true, '1'..='9') => Command::Shoot(key.to_string()),
+            (false, '1'..='9') => Command::Go(key.to_string()),
+            (false, 'b') => Command::Go("back".to_string()),
+            (false, 's') => {
+                shooting = true;
+                continue;
+            },
+            (false, 'f') => Command::Fight,
+            (false, 'r') => Command::Flee,
+            (false, 'w') | (false, 'z') => Command::Wait,
+            (false, 'q') => return (board, player, None),
+            _ => continue,
+        };
+        shooting = false;
+        let result = player.act(&mut board, cmd.clone());
+        replay.record(&cmd, &result);
+        match result {
+            Ok(events) => push_events(&mut log, events),
+            Err(e) => log.push(e),
+        }
+    }
+}
+
+fn push_events(log: &mut Vec<String>, events: Vec<Event>) {
+    for e in events {
+        log.extend(e.to_string().lines().map(|l| l.to_string()));
+    }
+}
+
+/// Draw the whole screen: the room and its exits on the left, the player's
+/// status and a map on the right, the message log below and a line of help.
+fn render(board: &Board, player: &Player, log: &[String], shooting: bool, rows: usize, cols: usize) -> String {
+    let room = board.room(player.location);
+    let log_height = cmp::max(6, rows / 3);
+    let top_height = rows - 1 - log_height;
+    let left_width = cols * 3 / 5;
+    let right_width = cols - left_width;
+
+    let exits: Vec<String> = board.neighbors(player.location).iter()
+        .enumerate()
+        .map(|(i, &r)| format!("[{}] {}", i + 1, board.room(r).name))
+        .collect();
+    let exits_height = cmp::min(exits.len() + 2, top_height / 2);
+    let mut about = Vec::new();
+    if let Some(ref description) = room.description {
+        about.extend(wrap(description, left_width - 2));
+        about.push(String::new());
+    }
+    about.extend(room.monsters.iter().map(|m| t!("ui.monster_here", name = m.name, hp = m.hp)));
+    about.extend(board.senses(player.location).iter().map(|s| s.to_string()));
+    let mut left = panel(&room.name, &about, left_width, top_height - exits_height);
+    left.extend(panel(&t!("tui.exits"), &exits, left_width, exits_height));
+
+    let mut status = vec![
+        format!("{}{}/{}", fit(&t!("tui.hp"), 10), player.hp, player.max_hp),
+        format!("{}{}", fit(&t!("tui.gold"), 10), player.gold),
+        format!("{}{}", fit(&t!("tui.arrows"), 10), player.arrows),
+    ];
+    for (item, n) in &player.items {
+        status.push(format!("{}{}", fit(item, 10), n));
+    }
+    let status_height = cmp::min(status.len() + 2, top_height / 2);
+    let map_height = top_height - status_height;
+    let map: Vec<String> = board.to_ascii(player.location, &player.stats.rooms_visited).lines().map(|l| l.to_string()).collect();
+    // Scroll the map so the player's room stays in view.
+    let here = map.iter().position(|l| l.starts_with('*')).unwrap_or(0);
+    let start = cmp::min(here, map.len().saturating_sub(map_height - 2));
+    let mut right = panel(&t!("tui.status"), &status, right_width, status_height);
+    right.extend(panel(&t!("tui.map"), &map[start..], right_width, map_height));
+
+    let shown = cmp::min(log.len(), log_height - 2);
+    let mut lines: Vec<String> = left.into_iter().zip(right).map(|(l, r)| l + &r).collect();
+    lines.extend(panel(&t!("tui.log"), &log[log.len() - shown..], cols, log_height));
+
+    let help = if shooting { t!("tui.shoot") } else { t!("tui.help") };
+    lines.push(format!("\x1b[7m{}\x1b[0m", fit(&help, cols)));
+    lines.join("\n")
+}
+
+/// A box `width` wide and `height` tall with `title` in its top border.
+/// Lines that do not fit are cut off.
+fn panel(title: &str, lines: &[String], width: usize, height: usize) -> Vec<String> {
+    let inner = width - 2;
+    let label = format!("- {} ", title);
+    let label = fit(&label, cmp::min(inner, width_of(&label)));
+    let mut out = vec![format!("+{}{}+", label, "-".repeat(inner - width_of(&label)))];
+    for i in 0..height.saturating_sub(2) {
+        let line = lines.get(i).map(|l| &l[..]).unwrap_or("");
+        out.push(format!("|{}|", fit(line, inner)));
+    }
+    out.push(format!("+{}+", "-".repeat(inner)));
+    out
+}
+
+/// How many columns a character takes up on the terminal: two for the wide
+/// characters of Chinese, Japanese and Korean, one for everything else.
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115f | 0x2e80..=0x303e | 0x3041..=0x33ff | 0x3400..=0x4dbf | 0x4e00..=0x9fff |
+        0xa000..=0xa4cf | 0xac00..=0xd7a3 | 0xf900..=0xfaff | 0xfe30..=0xfe4f | 0xff00..=0xff60 |
+        0xffe0..=0xffe6 => 2,
+        _ => 1,
+    }
+}
+
+/// How many columns `text` takes up on the terminal.
+fn width_of(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Pad or cut `text` to exactly `width` columns.
+fn fit(text: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut len = 0;
+    for c in text.chars() {
+        if len + char_width(c) > width {
+            break;
+        }
+        len += char_width(c);
+        out.push(c);
+    }
+    out.push_str(&" ".repeat(width - len));
+    out
+}
+
+/// Break `text` into lines at most `width` columns wide. Words too wide for
+/// a line, like sentences in languages written without spaces, are broken
+/// wherever they run out of room.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut cur = String::new();
+    for word in text.split_whitespace() {
+        if !cur.is_empty() && width_of(&cur) + 1 + width_of(word) > width {
+            lines.push(cur);
+            cur = String::new();
+        }
+        if !cur.is_empty() {
+            cur.push(' ');
+        }
+        for c in word.chars() {
+            if !cur.is_empty() && width_of(&cur) + char_width(c) > width {
+                lines.push(cur);
+                cur = String::new();
+            }
+            cur.push(c);
+        }
+    }
+    if !cur.is_empty() {
+        lines.push(cur);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fit, panel, render, wrap};
+    use game::board::Board;
+    use game::difficulty::Difficulty;
+    use game::player::Player;
+
+    #[test]
+    fn render_fills_the_screen() {
+        let level = r#"{"rooms": [{"name": "Entry", "curios": 0, "description": "A long and winding description."},
+                                  {"name": "Lair", "curios": 0, "wumpus": true}],
+                        "halls": [[0, 1]]}"#;
+        let mut board = Board::build_board(&mut level.as_bytes()).unwrap();
+        let (player, _) = Player::spawn(&mut board, Difficulty::default());
+        let log = vec!["Something happened.".to_string()];
+        let screen = render(&board, &player, &log, false, 24, 80);
+        let lines: Vec<&str> = screen.lines().collect();
+        assert_eq!(lines.len(), 24);
+        for l in &lines[..23] {
+            assert_eq!(l.chars().count(), 80, "{:?}", l);
+        }
+        assert!(screen.contains("[1] Lair"));
+        assert!(screen.contains("You smell a wumpus."));
+        assert!(screen.contains("Something happened."));
+        assert!(screen.contains("HP        25/25"));
+    }
+
+    #[test]
+    fn panels_and_wrapping() {
+        let lines = vec!["one".to_string(), "two".to_string(), "three".to_string()];
+        assert_eq!(panel("Log", &lines, 10, 4), vec!["+- Log --+", "|one     |", "|two     |", "+--------+"]);
+        assert_eq!(wrap("the quick brown fox", 9), vec!["the quick", "brown fox"]);
+        assert_eq!(wrap("ワンパスの臭いがする。", 8), vec!["ワンパス", "の臭いが", "する。"]);
+        assert_eq!(fit("ワンパス", 5), "ワン ");
+    }
+}
@@ -1,19 +1,24 @@
+use std::env;
+use std::fs::File;
 use std::io::{self, Write};
+use std::path::Path;
 
+use game::board::Board;
 use game::player::Player;
-use game::player::Command;
+use game::replay::Replay;
+use game::save;
+use game::score::{self, HighScore, HighScores};
+use parser::{self, Error, Input};
 
-#[derive(Debug)]
-enum Error {
-    Parse,
-    Quit,
-}
-
-pub fn game_loop(mut player: Player) {
+/// Play a game on the level loaded from `level`, recording the result in
+/// that level's high-score table and the game in `replay`. Returns the
+/// player as they finished.
+pub fn game_loop(mut board: Board, mut player: Player, level: &Path, replay: Replay) -> Player {
+    let mut replay = Some(replay);
+    print!("{}", describe(&board, &player));
     loop {
         // Print a user input prompt.
-        println!("{}\n\nExits are: {}.\n\nWhat wouldst thou deau?",
-                 player, player.location.borrow().neighbors_string());
+        println!("\n{}", t!("ui.prompt"));
         print!("> ");
         io::stdout().flush().unwrap();
 
@@ -26,45 +31,153 @@ pub fn game_loop(mut player: Player) {
                 break;
             }
             Ok(_) => {
-                let parse = parse_line(&buf);
-                if let Err(Error::Parse) = parse {
-                    println!("I do not know how to parse {}!", buf.trim());
-                } else if let Err(Error::Quit) = parse {
+                let location = player.location;
+                match parser::parse_line(&buf) {
+                    Err(Error::Parse) => {
+                        println!("{}", t!("ui.parse_error", input = buf.trim()));
+                    },
+                    Err(Error::Quit) => {
+                        println!("{}", t!("ui.bye"));
+                        break;
+                    },
+                    Ok(Input::Cmd(cmd)) => {
+                        let result = player.act(&mut board, cmd.clone());
+                        if let Some(ref mut replay) = replay {
+                            replay.record(&cmd, &result);
+                        }
+                        match result {
+                            Ok(events) => for e in events { println!("{}", e); },
+                            Err(e) => println!("{}", e),
+                        }
+                    },
+                    Ok(Input::Look) => print!("{}", describe(&board, &player)),
+                    Ok(Input::Inventory) => println!("{}", player.status()),
+                    Ok(Input::Map) => print!("{}", board.to_ascii(player.location, &player.stats.rooms_visited)),
+                    Ok(Input::Help) => println!("{}", t!("ui.help")),
+                    Ok(Input::Save(filename)) => {
+                        let result = File::create(&filename)
+                            .map_err(|e| e.to_string())
+                            .and_then(|mut f| save::save(&board, &player, &mut f));
+                        match result {
+                            Ok(()) => println!("{}", t!("ui.saved", file = filename)),
+                            Err(e) => println!("{}", t!("ui.save_failed", file = filename, error = e)),
+                        }
+                    },
+                    Ok(Input::Load(filename)) => {
+                        let result = File::open(&filename)
+                            .map_err(|e| e.to_string())
+                            .and_then(|mut f| save::load(&mut f));
+                        match result {
+                            Ok((b, p)) => {
+                                board = b;
+                                player = p;
+                                println!("{}", t!("ui.loaded", file = filename));
+                                if replay.take().is_some() {
+                                    println!("{}", t!("ui.not_recorded"));
+                                }
+                                print!("{}", describe(&board, &player));
+                            },
+                            Err(e) => println!("{}", t!("ui.load_failed", file = filename, error = e)),
+                        }
+                    },
+                }
+                if let Some(msg) = game_over(&player) {
+                    println!("{}", msg);
                     break;
-                } else if let Ok(cmd) = parse {
-                    if let Err(_) = player.act(cmd) {
-                        println!("I don't know how to act on {}!", buf.trim());
-                    }
                 }
-                if player.hp <= 0 {
-                    println!("You try in vain to shovel more wall chicken into \
-                              your mouth, but you've been impaled by too many spikes or Wumpi :(");
-                    println!("You Lose!");
-                    return;
+                if player.location != location {
+                    print!("{}", describe(&board, &player));
                 }
             }
         }
     }
-    println!("Score: {}", player.gold * 1000);
+    print!("\n{}", summary(&board, &player));
+    // A loaded save may be from another level, so only a game played here
+    // from the start goes in this level's table.
+    if let Some(replay) = replay {
+        record_score(&player, level);
+        save_replay(&replay, level);
+    }
+    player
+}
+
+/// Keep the game's replay next to the level.
+pub fn save_replay(replay: &Replay, level: &Path) {
+    match replay.save_next_to(level) {
+        Ok(path) => println!("{}", t!("ui.replay_saved", file = path.display())),
+        Err(e) => println!("{}", t!("ui.replay_failed", error = e)),
+    }
+}
+
+/// Add the run to the level's high-score table and show the table. Games
+/// that did not end in a win or a loss, because the player quit or input
+/// ran out, are not recorded.
+pub fn record_score(player: &Player, level: &Path) {
+    if game_over(player).is_none() {
+        return;
+    }
+    let path = HighScores::path_for(level);
+    let mut scores = match HighScores::load(&path) {
+        Ok(scores) => scores,
+        Err(e) => {
+            println!("{}", t!("ui.scores_read_failed", error = e));
+            return;
+        },
+    };
+    let name = env::var("USER").unwrap_or_else(|_| "Anonymous".to_string());
+    let entry = HighScore { name, score: score::score(player), won: player.won, turns: player.stats.turns };
+    if let Some(rank) = scores.add(entry) {
+        println!("{}", t!("ui.high_score", rank = rank));
+        if let Err(e) = scores.save(&path) {
+            println!("{}", t!("ui.scores_save_failed", error = e));
+        }
+    }
+    println!("\n{}", t!("ui.high_scores"));
+    for (i, e) in scores.entries.iter().enumerate() {
+        let won = if e.won { format!("  {}", t!("ui.won")) } else { String::new() };
+        println!("{:>2}. {:<12} {:>6}  {}{}", i + 1, e.name, e.score, t!("ui.turns", turns = format!("{:>4}", e.turns)), won);
+    }
 }
 
-fn parse_line(buf: &String) -> Result<Command, Error> {
-    use game::player::Command::*;
+/// A summary of the player's run and its score.
+pub fn summary(board: &Board, player: &Player) -> String {
+    let stats = &player.stats;
+    t!("ui.summary", visited = stats.rooms_visited.len(), rooms = board.len(), turns = stats.turns,
+       damage = stats.damage_taken, food = stats.food_eaten, healed = stats.hp_healed,
+       kills = stats.wumpus_kills, monsters = stats.monster_kills, gold = player.gold, score = score::score(player))
+}
 
-    let tokens = buf.trim().split_whitespace();
-    let mut tokens = tokens.map(|t| String::from(t).to_lowercase());
+/// The player's surroundings: the room, warnings and numbered exits.
+pub fn describe(board: &Board, player: &Player) -> String {
+    let room = board.room(player.location);
+    let mut text = format!("\n{}\n", t!("ui.location", room = room.name));
+    if let Some(ref description) = room.description {
+        text.push_str(&format!("{}\n", description));
+    }
+    text.push_str(&format!("\n{}\n\n", player.status()));
+    for monster in &room.monsters {
+        text.push_str(&format!("{}\n", t!("ui.monster_here", name = monster.name, hp = monster.hp)));
+    }
+    for sense in board.senses(player.location) {
+        text.push_str(&format!("{}\n", sense));
+    }
+    let exits: Vec<String> = board.neighbors(player.location).iter()
+        .enumerate()
+        .map(|(i, &r)| format!("[{}] {}", i + 1, board.room(r).name))
+        .collect();
+    text.push_str(&format!("{}\n", t!("ui.exits", exits = exits.join(", "))));
+    text
+}
 
-    let cmd = try!(tokens.next().ok_or(Error::Parse));
-    if cmd == "go" {
-        let room = try!(tokens.next().ok_or(Error::Parse));
-        Ok(Go(room))
-    } else if cmd == "shoot" {
-        let room = try!(tokens.next().ok_or(Error::Parse));
-        Ok(Shoot(room))
-    } else if cmd == "quit" {
-        println!("Bye forever :(");
-        Err(Error::Quit)
+/// The closing message if the game is over for `player`.
+pub fn game_over(player: &Player) -> Option<String> {
+    if player.won {
+        Some(t!("ui.win"))
+    } else if player.hp <= 0 {
+        Some(t!("ui.lose_hp"))
+    } else if player.arrows <= 0 {
+        Some(t!("ui.lose_arrows"))
     } else {
-        Err(Error::Parse)
+        None
     }
 }
@@ -0,0 +1,168 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+const LEVEL: &str = r#"{"rooms": [{"name": "Entry", "curios": []},
+                                   {"name": "Hall", "curios": []},
+                                   {"name": "Lair", "curios": [], "wumpus": true}],
+                        "halls": [[0, 1], [1, 2]]}"#;
+
+const DEN: &str = r#"{"rooms": [{"name": "Entry", "curios": []},
+                                 {"name": "Den", "curios": [],
+                                  "monsters": [{"name": "ghoul", "hp": 100, "attack": 1, "loot": []}]},
+                                 {"name": "Lair", "curios": [], "wumpus": true}],
+                      "halls": [[0, 1], [0, 2]]}"#;
+
+/// A running server and the level file written for it, which are killed
+/// and removed when the test is done.
+struct Server {
+    child: Child,
+    level: PathBuf,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = fs::remove_file(&self.level);
+    }
+}
+
+/// Start a server on an ephemeral port and return it with its address.
+fn start_server(name: &str, level: &str) -> (Server, String) {
+    let path = env::temp_dir().join(format!("hw05-{}-{}.json", name, std::process::id()));
+    File::create(&path).unwrap().write_all(level.as_bytes()).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hw05"))
+        .arg(&path)
+        .args(["--lang", "en"])
+        .arg("--serve")
+        .arg("127.0.0.1:0")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+    let addr = line.trim().trim_start_matches("Listening on ").to_string();
+    (Server { child, level: path }, addr)
+}
+
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    fn connect(addr: &str) -> Client {
+        let stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        Client { reader: BufReader::new(stream.try_clone().unwrap()), writer: stream }
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.writer, "{}", line).unwrap();
+    }
+
+    /// Read lines until one contains `needle`, failing on timeout or EOF.
+    fn expect(&mut self, needle: &str) {
+        loop {
+            let mut line = String::new();
+            let n = self.reader.read_line(&mut line).unwrap_or_else(|_| panic!("timed out waiting for {:?}", needle));
+            assert!(n > 0, "connection closed waiting for {:?}", needle);
+            if line.contains(needle) {
+                return;
+            }
+        }
+    }
+}
+
+#[test]
+fn players_see_each_other() {
+    let (_server, addr) = start_server("arrive", LEVEL);
+
+    let mut alice = Client::connect(&addr);
+    alice.expect("Welcome, Player 1!");
+    alice.expect("Exits are: [1] Hall.");
+    let mut bob = Client::connect(&addr);
+    bob.expect("Welcome, Player 2!");
+    alice.expect("Player 2 arrives.");
+
+    alice.send("go hall");
+    alice.expect("You find yourself in Hall.");
+    alice.expect("You smell a wumpus.");
+    bob.expect("Player 1 leaves.");
+
+    bob.send("go hall");
+    bob.expect("You find yourself in Hall.");
+    alice.expect("Player 2 arrives.");
+
+    alice.send("shoot lair");
+    alice.expect("You Win!");
+    bob.expect("Player 1 has slain the wumpus!");
+    bob.expect("The hunt is over");
+    bob.expect("Wumpus kills:  0");
+
+    let mut carol = Client::connect(&addr);
+    carol.expect("The hunt is over");
+}
+
+#[test]
+fn monsters_strike_on_each_players_own_turn() {
+    let (_server, addr) = start_server("monsters", DEN);
+
+    let mut alice = Client::connect(&addr);
+    alice.expect("Exits are:");
+    let mut bob = Client::connect(&addr);
+    bob.expect("Exits are:");
+
+    alice.send("go den");
+    alice.expect("The ghoul strikes you for 1 damage.");
+    // Bob's turns do not cost Alice HP, but the ghoul stays in the den to
+    // strike her on her own next turn.
+    for _ in 0..3 {
+        bob.send("wait");
+        bob.expect("Time passes.");
+    }
+    alice.send("inventory");
+    alice.expect("You have 24 HP");
+    alice.send("wait");
+    alice.expect("The ghoul strikes you for 1 damage.");
+}
+
+#[test]
+fn bad_commands_and_quit() {
+    let (_server, addr) = start_server("quit", LEVEL);
+
+    let mut client = Client::connect(&addr);
+    client.expect("Exits are:");
+    client.send("dance");
+    client.expect("I do not know how to parse dance!");
+    client.send("go kitchen");
+    client.expect("There is no hall to kitchen.");
+    client.send("save game.json");
+    client.expect("not available");
+    client.send("quit");
+    client.expect("Bye forever :(");
+}
+
+#[test]
+fn stalled_client_does_not_block_others() {
+    let (_server, addr) = start_server("stall", LEVEL);
+
+    let mut alice = Client::connect(&addr);
+    alice.expect("Exits are:");
+
+    // Bob asks for far more help than the socket buffers hold and never
+    // reads any of it.
+    let mut bob = Client::connect(&addr);
+    alice.expect("Player 2 arrives.");
+    bob.writer.write_all("help\n".repeat(20_000).as_bytes()).unwrap();
+    bob.send("go hall");
+
+    alice.send("look");
+    alice.expect("You find yourself in Entry.");
+    alice.expect("Player 2 leaves.");
+}